@@ -0,0 +1,181 @@
+#[macro_use]
+extern crate criterion;
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use checkco::{Options, Schema, Verdict};
+use criterion::Criterion;
+use serde_json::{Map, Value};
+
+/// Pairs of `(name, derived, base)`: an older version is checked against a newer one.
+const CORPUS: &[(&str, &str, &str)] = &[
+    (
+        "github-action",
+        "github-action-zizmor-1.10",
+        "github-action-zizmor-1.30",
+    ),
+    ("podmonitor", "podmonitor-0.72", "podmonitor-0.89"),
+    ("cluster", "cluster-v1alpha4", "cluster-v1beta1"),
+];
+
+/// Tracks live heap usage to report memory consumed by a single check.
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn load(name: &str) -> Schema {
+    let path = format!(
+        "{}/benches/corpus/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let source = fs::read_to_string(&path).unwrap();
+    let value = approximate(serde_json::from_str(&source).unwrap());
+    value.to_string().parse().unwrap()
+}
+
+/// Rewrites keywords that checking doesn't support.
+///
+/// `oneOf` is read as `anyOf` and `if` is dropped in favor of `anyOf` of its branches,
+/// which only widens the schema.
+fn approximate(value: Value) -> Value {
+    let schema = match value {
+        Value::Object(schema) => schema,
+        value => return value,
+    };
+
+    let mut res = Map::new();
+
+    for (key, value) in schema {
+        let value = match key.as_str() {
+            "properties" | "patternProperties" | "definitions" => match value {
+                Value::Object(map) => {
+                    Value::Object(map.into_iter().map(|(k, v)| (k, approximate(v))).collect())
+                }
+                value => value,
+            },
+            "allOf" | "anyOf" | "oneOf" => match value {
+                Value::Array(schemas) => {
+                    Value::Array(schemas.into_iter().map(approximate).collect())
+                }
+                value => value,
+            },
+            "items"
+            | "additionalItems"
+            | "additionalProperties"
+            | "contains"
+            | "propertyNames"
+            | "not"
+            | "if"
+            | "then"
+            | "else" => approximate(value),
+            _ => value,
+        };
+
+        let key = if key == "oneOf" { "anyOf".into() } else { key };
+        res.insert(key, value);
+    }
+
+    if res.contains_key("then") || res.contains_key("else") {
+        res.remove("if");
+
+        let branches = ["then", "else"]
+            .iter()
+            .map(|b| res.remove(*b).unwrap_or_else(|| json!({})))
+            .collect::<Vec<_>>();
+
+        let all_of = res
+            .entry("allOf")
+            .or_insert_with(|| Value::Array(Vec::new()));
+
+        if let Value::Array(all_of) = all_of {
+            all_of.push(json!({ "anyOf": branches }));
+        }
+    }
+
+    Value::Object(res)
+}
+
+fn check(derived: &Schema, base: &Schema) -> Verdict {
+    checkco::check(derived.clone(), base.clone()).unwrap()
+}
+
+/// Prints metrics that criterion doesn't measure: spread size, the verdict and memory.
+fn report(name: &str, derived: &Schema, base: &Schema) {
+    let options = Options::default();
+    let units = checkco::count_units(derived.clone(), &options).unwrap()
+        + checkco::count_units(base.clone(), &options).unwrap();
+
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+
+    let compatible = match check(derived, base) {
+        Verdict::Success => true,
+        Verdict::Failure(_) => false,
+    };
+
+    println!(
+        "{}: {} units, compatible: {}, {} allocations, {} KiB peak",
+        name,
+        units,
+        compatible,
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        (PEAK.load(Ordering::Relaxed) - baseline) / 1024,
+    );
+}
+
+fn bench_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check");
+
+    for (name, derived, base) in CORPUS {
+        let derived = load(derived);
+        let base = load(base);
+        report(name, &derived, &base);
+        group.bench_function(*name, |b| b.iter(|| check(&derived, &base)));
+    }
+
+    group.finish();
+}
+
+fn bench_spread(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spread");
+    let options = Options::default();
+
+    for (name, derived, _) in CORPUS {
+        let schema = load(derived);
+
+        group.bench_function(*name, |b| {
+            b.iter(|| checkco::count_units(schema.clone(), &options).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_check, bench_spread);
+criterion_main!(benches);
@@ -0,0 +1,232 @@
+//! Building blocks shared by front-ends for API description formats.
+//!
+//! Both OpenAPI and AsyncAPI embed JSON Schemas and keep reusable ones in `components/schemas`.
+
+use serde_json::{Map, Value};
+
+use error::Error;
+use matcher::Mismatch;
+use options::Options;
+use schema::Schema;
+use Verdict;
+
+#[cfg(test)]
+mod spec;
+
+/// The flavor of JSON Schema used by a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// The subset of JSON Schema with `nullable` and boolean exclusive limits.
+    OpenApi30,
+    /// JSON Schema itself, used by OpenAPI 3.1 and AsyncAPI.
+    JsonSchema,
+}
+
+pub struct Document<'a> {
+    pub root: &'a Value,
+    dialect: Dialect,
+    /// Converted `components/schemas`, attached to every schema to resolve `$ref`s.
+    definitions: Value,
+}
+
+impl<'a> Document<'a> {
+    pub fn new(root: &'a Value, dialect: Dialect) -> Document<'a> {
+        let definitions = root.pointer("/components/schemas").map_or_else(
+            || Value::Object(Map::new()),
+            |s| convert(s.clone(), dialect, true),
+        );
+
+        Document {
+            root,
+            dialect,
+            definitions,
+        }
+    }
+
+    /// Follows local `$ref`s of objects other than schemas.
+    pub fn follow(&self, mut value: &'a Value) -> Result<&'a Value, Error> {
+        // Bounded to not loop on cyclic references.
+        for _ in 0..32 {
+            let reference = match value.get("$ref").and_then(Value::as_str) {
+                Some(reference) => reference,
+                None => return Ok(value),
+            };
+
+            value = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+                .ok_or_else(|| Error::UnresolvedRef(reference.into()))?;
+        }
+
+        Err(Error::Unsupported("recursive $ref"))
+    }
+
+    pub fn schema(&self, schema: &Value) -> Result<Schema, Error> {
+        let mut schema = convert(schema.clone(), self.dialect, false);
+
+        if let Value::Object(ref mut map) = schema {
+            map.insert("definitions".into(), self.definitions.clone());
+        }
+
+        Ok(::serde_json::from_value(schema)?)
+    }
+}
+
+/// Checks that the sent schema is a subtype of the accepted one.
+///
+/// Mismatches are collected into `out` with pointers prefixed by `loc`.
+pub fn check_schema(
+    sent: Schema,
+    accepted: Schema,
+    options: &Options,
+    loc: &str,
+    out: &mut Vec<Mismatch>,
+) -> Result<(), Error> {
+    if let Verdict::Failure(mismatches) = ::check_with(sent, accepted, options)? {
+        out.extend(mismatches.into_iter().map(|mismatch| Mismatch {
+            pointer: format!("{}{}", loc, mismatch.pointer),
+            keyword: mismatch.keyword,
+        }));
+    }
+
+    Ok(())
+}
+
+pub fn verdict(mut mismatches: Vec<Mismatch>) -> Verdict {
+    if mismatches.is_empty() {
+        return Verdict::Success;
+    }
+
+    mismatches.sort();
+    mismatches.dedup();
+
+    Verdict::Failure(mismatches)
+}
+
+/// Lists members of the object, if it is one.
+pub fn entries(value: Option<&Value>) -> Vec<(&str, &Value)> {
+    value
+        .and_then(Value::as_object)
+        .map_or_else(Vec::new, |map| {
+            map.iter().map(|(k, v)| (k.as_str(), v)).collect()
+        })
+}
+
+pub fn pointer(segments: &[&str]) -> String {
+    segments.iter().map(|s| format!("/{}", escape(s))).collect()
+}
+
+pub fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Converts a schema object of the dialect into a JSON Schema.
+///
+/// `definitions` marks a map of schemas (e.g. `components/schemas`) instead of a single schema.
+fn convert(schema: Value, dialect: Dialect, definitions: bool) -> Value {
+    let mut map = match schema {
+        Value::Object(map) => map,
+        other => return other,
+    };
+
+    if definitions {
+        return Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, convert(v, dialect, false)))
+                .collect(),
+        );
+    }
+
+    if let Some(Value::String(reference)) = map.get_mut("$ref") {
+        if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+            *reference = format!("#/definitions/{}", name);
+        }
+    }
+
+    for (keyword, value) in map.iter_mut() {
+        match keyword.as_str() {
+            "properties" | "patternProperties" | "definitions" => {
+                *value = convert(value.take(), dialect, true);
+            }
+            "items"
+            | "additionalItems"
+            | "additionalProperties"
+            | "propertyNames"
+            | "contains"
+            | "not"
+            | "if"
+            | "then"
+            | "else" => {
+                *value = match value.take() {
+                    Value::Array(items) => Value::Array(
+                        items
+                            .into_iter()
+                            .map(|v| convert(v, dialect, false))
+                            .collect(),
+                    ),
+                    other => convert(other, dialect, false),
+                };
+            }
+            "allOf" | "anyOf" | "oneOf" => {
+                if let Value::Array(items) = value {
+                    for item in items.iter_mut() {
+                        *item = convert(item.take(), dialect, false);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if dialect == Dialect::OpenApi30 {
+        convert_exclusive(&mut map, "exclusiveMaximum", "maximum");
+        convert_exclusive(&mut map, "exclusiveMinimum", "minimum");
+
+        if map.remove("nullable") == Some(Value::Bool(true)) {
+            return nullable(map);
+        }
+    }
+
+    Value::Object(map)
+}
+
+/// Converts boolean `exclusiveMaximum` of OpenAPI 3.0 into a number.
+fn convert_exclusive(map: &mut Map<String, Value>, exclusive: &str, inclusive: &str) {
+    match map.get(exclusive) {
+        Some(Value::Bool(true)) => {
+            if let Some(limit) = map.remove(inclusive) {
+                map.insert(exclusive.into(), limit);
+            } else {
+                map.remove(exclusive);
+            }
+        }
+        Some(Value::Bool(false)) => {
+            map.remove(exclusive);
+        }
+        _ => {}
+    }
+}
+
+/// Adds `null` to values allowed by the schema.
+fn nullable(mut map: Map<String, Value>) -> Value {
+    if let Some(Value::Array(variants)) = map.get_mut("enum") {
+        if !variants.contains(&Value::Null) {
+            variants.push(Value::Null);
+        }
+    }
+
+    match map.get_mut("type") {
+        Some(Value::String(type_)) => {
+            let type_ = Value::String(type_.clone());
+            map.insert("type".into(), json!([type_, "null"]));
+        }
+        Some(Value::Array(types)) => {
+            if !types.contains(&json!("null")) {
+                types.push(json!("null"));
+            }
+        }
+        _ => return json!({ "anyOf": [{ "type": "null" }, map] }),
+    }
+
+    Value::Object(map)
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn it_should_convert_nullable() {
+    let schema = json!({"type": "string", "nullable": true});
+    assert_eq!(
+        convert(schema, Dialect::OpenApi30, false),
+        json!({"type": ["string", "null"]})
+    );
+
+    let schema = json!({"enum": ["a"], "nullable": true});
+    assert_eq!(
+        convert(schema, Dialect::OpenApi30, false),
+        json!({"anyOf": [{"type": "null"}, {"enum": ["a", null]}]})
+    );
+}
+
+#[test]
+fn it_should_ignore_nullable_in_json_schema() {
+    let schema = json!({"type": "string", "nullable": true});
+    assert_eq!(convert(schema.clone(), Dialect::JsonSchema, false), schema);
+}
+
+#[test]
+fn it_should_convert_boolean_exclusive_limits() {
+    let schema =
+        json!({"maximum": 5, "exclusiveMaximum": true, "minimum": 0, "exclusiveMinimum": false});
+    assert_eq!(
+        convert(schema, Dialect::OpenApi30, false),
+        json!({"exclusiveMaximum": 5, "minimum": 0})
+    );
+}
+
+#[test]
+fn it_should_rewrite_refs_to_components() {
+    let schema = json!({"properties": {"pet": {"$ref": "#/components/schemas/Pet"}}});
+    assert_eq!(
+        convert(schema, Dialect::OpenApi30, false),
+        json!({"properties": {"pet": {"$ref": "#/definitions/Pet"}}})
+    );
+}
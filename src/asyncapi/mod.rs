@@ -0,0 +1,296 @@
+//! Compatibility checking of AsyncAPI 2.x and 3.x documents.
+//!
+//! Channels are paired by their address and messages by their operation in 2.x and identifier.
+//! Messages without one are paired only with ones having the same payload and headers, so changing
+//! them is reported as removing and adding a message. Payloads and headers of paired messages are
+//! checked in the direction defined by `Compatibility`.
+//!
+//! Schemas are JSON Schemas, or Avro ones if `schemaFormat` says so.
+
+use serde_json::Value;
+
+use api::{self, Dialect, Document};
+use avro;
+use compatibility::Compatibility;
+use error::Error;
+use matcher::Mismatch;
+use options::Options;
+use schema::Schema;
+use Verdict;
+
+#[cfg(test)]
+mod spec;
+
+/// Checks that the derived document is compatible with the base one.
+pub fn check(
+    derived: &Value,
+    base: &Value,
+    compatibility: Compatibility,
+    max_units: usize,
+) -> Result<Verdict, Error> {
+    let derived = open(derived)?;
+    let base = open(base)?;
+
+    let mut checker = Checker {
+        derived: &derived,
+        base: &base,
+        compatibility,
+        options: Options {
+            max_units,
+            ..Options::default()
+        },
+        mismatches: Vec::new(),
+    };
+
+    checker.check_channels()?;
+
+    Ok(api::verdict(checker.mismatches))
+}
+
+fn open(root: &Value) -> Result<Document<'_>, Error> {
+    match root.get("asyncapi").and_then(Value::as_str) {
+        Some(v) if v.starts_with("2.") || v.starts_with("3.") => {}
+        Some(_) => return Err(Error::Unsupported("AsyncAPI version")),
+        None => {
+            return Err(Error::InvalidSchema(
+                "`asyncapi` version is required".into(),
+            ))
+        }
+    }
+
+    Ok(Document::new(root, Dialect::JsonSchema))
+}
+
+struct Channel<'a> {
+    /// The address for 3.x and the name for 2.x documents.
+    address: &'a str,
+    loc: String,
+    messages: Vec<Message<'a>>,
+}
+
+struct Message<'a> {
+    /// `publish` or `subscribe` in 2.x, where messages belong to operations.
+    op: Option<&'static str>,
+    /// `messageId` or `name`, which are optional in 2.x.
+    id: Option<String>,
+    loc: String,
+    value: &'a Value,
+}
+
+impl<'a> Message<'a> {
+    fn pairs(&self, other: &Message) -> bool {
+        if self.op != other.op {
+            return false;
+        }
+
+        match (&self.id, &other.id) {
+            (Some(id), Some(other_id)) => id == other_id,
+            (None, None) => ["payload", "headers"]
+                .iter()
+                .all(|k| self.value.get(*k) == other.value.get(*k)),
+            _ => false,
+        }
+    }
+}
+
+struct Checker<'a> {
+    derived: &'a Document<'a>,
+    base: &'a Document<'a>,
+    compatibility: Compatibility,
+    options: Options,
+    mismatches: Vec<Mismatch>,
+}
+
+impl<'a> Checker<'a> {
+    fn check_channels(&mut self) -> Result<(), Error> {
+        let derived_channels = channels(self.derived)?;
+
+        for base_channel in channels(self.base)? {
+            let derived_channel = derived_channels
+                .iter()
+                .find(|c| c.address == base_channel.address);
+
+            let derived_channel = match derived_channel {
+                Some(derived_channel) => derived_channel,
+                None => {
+                    // Messages already written to the channel can't be read anymore.
+                    if self.compatibility.is_backward() {
+                        self.mismatch(base_channel.loc, "channel");
+                    }
+
+                    continue;
+                }
+            };
+
+            self.check_messages(&derived_channel.messages, &base_channel.messages)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_messages(
+        &mut self,
+        derived: &[Message<'a>],
+        base: &[Message<'a>],
+    ) -> Result<(), Error> {
+        for message in base {
+            if !derived.iter().any(|m| m.pairs(message)) && self.compatibility.is_backward() {
+                self.mismatch(message.loc.clone(), "message");
+            }
+        }
+
+        for message in derived {
+            match base.iter().find(|m| m.pairs(message)) {
+                Some(base_message) => self.check_message(message, base_message)?,
+                None if self.compatibility.is_forward() => {
+                    self.mismatch(message.loc.clone(), "message")
+                }
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_message(&mut self, derived: &Message<'a>, base: &Message<'a>) -> Result<(), Error> {
+        for keyword in &["payload", "headers"] {
+            let loc = format!("{}/{}", derived.loc, keyword);
+            let derived_schema = schema(self.derived, derived.value.get(*keyword), derived.value)?;
+            let base_schema = schema(self.base, base.value.get(*keyword), base.value)?;
+
+            if self.compatibility.is_backward() {
+                let (sent, accepted) = (base_schema.clone(), derived_schema.clone());
+                api::check_schema(sent, accepted, &self.options, &loc, &mut self.mismatches)?;
+            }
+
+            if self.compatibility.is_forward() {
+                let (sent, accepted) = (derived_schema, base_schema);
+                api::check_schema(sent, accepted, &self.options, &loc, &mut self.mismatches)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn mismatch(&mut self, pointer: String, keyword: &'static str) {
+        self.mismatches.push(Mismatch { pointer, keyword });
+    }
+}
+
+/// Collects channels with their messages, which are placed differently in 2.x and 3.x.
+fn channels<'a>(doc: &Document<'a>) -> Result<Vec<Channel<'a>>, Error> {
+    let v3 = doc
+        .root
+        .get("asyncapi")
+        .and_then(Value::as_str)
+        .is_some_and(|v| v.starts_with("3."));
+
+    let mut channels = Vec::new();
+
+    for (name, channel) in api::entries(doc.root.get("channels")) {
+        let channel = doc.follow(channel)?;
+        let loc = api::pointer(&["channels", name]);
+        let mut messages = Vec::new();
+
+        if v3 {
+            for (id, message) in api::entries(channel.get("messages")) {
+                messages.push(Message {
+                    op: None,
+                    id: Some(id.into()),
+                    loc: format!("{}/messages/{}", loc, api::escape(id)),
+                    value: doc.follow(message)?,
+                });
+            }
+        } else {
+            for op in &["publish", "subscribe"] {
+                let message = match channel.pointer(&format!("/{}/message", op)) {
+                    Some(message) => doc.follow(message)?,
+                    None => continue,
+                };
+
+                let loc = format!("{}/{}/message", loc, op);
+
+                let variants = match message.get("oneOf").and_then(Value::as_array) {
+                    Some(variants) => variants
+                        .iter()
+                        .enumerate()
+                        .map(|(i, m)| (format!("{}/oneOf/{}", loc, i), m))
+                        .collect(),
+                    None => vec![(loc, message)],
+                };
+
+                for (loc, message) in variants {
+                    let message = doc.follow(message)?;
+
+                    let id = ["messageId", "name"]
+                        .iter()
+                        .find_map(|k| message.get(*k).and_then(Value::as_str))
+                        .map(String::from);
+
+                    // The same message may be listed twice, but it belongs to either operation.
+                    let listed = messages
+                        .iter()
+                        .any(|m: &Message| m.op == Some(*op) && m.id == id);
+
+                    if id.is_none() || !listed {
+                        messages.push(Message {
+                            op: Some(*op),
+                            id,
+                            loc,
+                            value: message,
+                        });
+                    }
+                }
+            }
+        }
+
+        let address = match channel.get("address").and_then(Value::as_str) {
+            Some(address) if v3 => address,
+            _ => name,
+        };
+
+        channels.push(Channel {
+            address,
+            loc,
+            messages,
+        });
+    }
+
+    Ok(channels)
+}
+
+/// Extracts the schema of the payload or headers of the message.
+///
+/// A missing schema allows anything.
+fn schema<'a>(
+    doc: &Document<'a>,
+    schema: Option<&'a Value>,
+    message: &Value,
+) -> Result<Schema, Error> {
+    let schema = match schema {
+        Some(schema) => schema,
+        None => return doc.schema(&json!({})),
+    };
+
+    // 3.x allows to specify the format for every schema.
+    let (format, schema) = match (schema.get("schemaFormat"), schema.get("schema")) {
+        (Some(format), Some(schema)) => (Some(format), schema),
+        _ => (message.get("schemaFormat"), schema),
+    };
+
+    match format.and_then(Value::as_str) {
+        None => doc.schema(schema),
+        Some(f) if is_json_schema(f) => doc.schema(schema),
+        // Avro schemas name their types themselves, so only the schema itself is followed.
+        Some(f) if f.starts_with("application/vnd.apache.avro") => {
+            avro::convert(doc.follow(schema)?)
+        }
+        Some(_) => Err(Error::Unsupported("schemaFormat")),
+    }
+}
+
+fn is_json_schema(format: &str) -> bool {
+    format.starts_with("application/vnd.aai.asyncapi")
+        || format.starts_with("application/schema+json")
+        || format.starts_with("application/schema+yaml")
+}
@@ -0,0 +1,64 @@
+use super::*;
+
+fn convert(payload: &Value, message: &Value) -> Result<Schema, Error> {
+    let root = json!({"asyncapi": "3.0.0"});
+    schema(
+        &Document::new(&root, Dialect::JsonSchema),
+        Some(payload),
+        message,
+    )
+}
+
+fn equivalent(schema: Schema, expected: Value) -> bool {
+    ::equivalent(schema, ::serde_json::from_value(expected).unwrap()).unwrap()
+}
+
+#[test]
+fn it_should_accept_json_schema_formats() {
+    let message = json!({"schemaFormat": "application/schema+json;version=draft-07"});
+    assert!(convert(&json!({"type": "string"}), &message).is_ok());
+
+    let message = json!({"schemaFormat": "application/vnd.google.protobuf;version=3"});
+    match convert(&json!({"type": "string"}), &message) {
+        Err(Error::Unsupported("schemaFormat")) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_convert_avro_schemas() {
+    let message = json!({"schemaFormat": "application/vnd.apache.avro;version=1.9.0"});
+    let payload = json!({"type": "record", "name": "A", "fields": [{"name": "a", "type": "int"}]});
+    let schema = convert(&payload, &message).unwrap();
+
+    assert!(equivalent(
+        schema,
+        json!({
+            "type": "object",
+            "properties": {"a": {"type": "integer", "minimum": -2147483648, "maximum": 2147483647}},
+            "required": ["a"],
+        })
+    ));
+}
+
+#[test]
+fn it_should_unwrap_multi_format_schemas() {
+    let payload = json!({
+        "schemaFormat": "application/vnd.aai.asyncapi+json;version=3.0.0",
+        "schema": {"type": "string"},
+    });
+
+    let schema = convert(&payload, &json!({})).unwrap();
+    assert!(equivalent(schema, json!({"type": "string"})));
+}
+
+#[test]
+fn it_should_allow_anything_without_schema() {
+    let root = json!({"asyncapi": "3.0.0"});
+    let doc = Document::new(&root, Dialect::JsonSchema);
+
+    assert!(equivalent(
+        schema(&doc, None, &json!({})).unwrap(),
+        json!({})
+    ));
+}
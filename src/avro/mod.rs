@@ -0,0 +1,246 @@
+//! Conversion of Avro schemas into JSON Schemas.
+//!
+//! Values are expected in their plain JSON representation: unions aren't wrapped into objects.
+//! Logical types are encoded as their underlying types, so they are only noted in `$comment`.
+
+use serde_json::{Map, Value};
+
+use api;
+use error::Error;
+use schema::Schema;
+
+#[cfg(test)]
+mod spec;
+
+const INT_MIN: i64 = i32::MIN as i64;
+const INT_MAX: i64 = i32::MAX as i64;
+
+/// Converts the Avro schema into a JSON Schema.
+///
+/// Named types (records, enums and fixed) are also added to `definitions`,
+/// so references to them by name become `$ref`s.
+pub fn convert(avro: &Value) -> Result<Schema, Error> {
+    let mut converter = Converter::default();
+    let mut schema = converter.convert(avro, "")?;
+
+    if !converter.definitions.is_empty() {
+        if let Value::Object(ref mut map) = schema {
+            map.insert("definitions".into(), Value::Object(converter.definitions));
+        }
+    }
+
+    Ok(::serde_json::from_value(schema)?)
+}
+
+#[derive(Default)]
+struct Converter {
+    definitions: Map<String, Value>,
+}
+
+impl Converter {
+    fn convert(&mut self, avro: &Value, namespace: &str) -> Result<Value, Error> {
+        match avro {
+            Value::String(name) => self.convert_name(name, namespace),
+            Value::Array(variants) => {
+                let variants = variants
+                    .iter()
+                    .map(|v| self.convert(v, namespace))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(json!({ "anyOf": variants }))
+            }
+            Value::Object(map) => self.convert_complex(map, namespace),
+            _ => Err(invalid("a type must be a name, an array or an object")),
+        }
+    }
+
+    fn convert_name(&mut self, name: &str, namespace: &str) -> Result<Value, Error> {
+        let schema = match name {
+            "null" => json!({"type": "null"}),
+            "boolean" => json!({"type": "boolean"}),
+            "int" => json!({"type": "integer", "minimum": INT_MIN, "maximum": INT_MAX}),
+            "long" => json!({"type": "integer", "minimum": i64::MIN, "maximum": i64::MAX}),
+            "float" | "double" => json!({"type": "number"}),
+            "bytes" | "string" => json!({"type": "string"}),
+            _ => {
+                let candidates = [fullname(name, namespace), name.to_owned()];
+
+                let fullname = candidates
+                    .iter()
+                    .find(|n| self.definitions.contains_key(*n))
+                    .ok_or_else(|| Error::UnresolvedRef(name.into()))?;
+
+                json!({ "$ref": format!("#/definitions/{}", api::escape(fullname)) })
+            }
+        };
+
+        Ok(schema)
+    }
+
+    fn convert_complex(
+        &mut self,
+        map: &Map<String, Value>,
+        namespace: &str,
+    ) -> Result<Value, Error> {
+        let type_ = map
+            .get("type")
+            .ok_or_else(|| invalid("`type` is required"))?;
+
+        let type_ = match type_ {
+            Value::String(type_) => type_.as_str(),
+            // Types can be nested: `{"type": {"type": "array", ...}}`.
+            other => return self.convert(other, namespace),
+        };
+
+        let mut schema = match type_ {
+            "record" | "error" | "enum" | "fixed" => {
+                return self.convert_named(type_, map, namespace);
+            }
+            "array" => {
+                let items = map
+                    .get("items")
+                    .ok_or_else(|| invalid("`items` is required"))?;
+                json!({"type": "array", "items": self.convert(items, namespace)?})
+            }
+            "map" => {
+                let values = map
+                    .get("values")
+                    .ok_or_else(|| invalid("`values` is required"))?;
+                json!({"type": "object", "additionalProperties": self.convert(values, namespace)?})
+            }
+            name => self.convert_name(name, namespace)?,
+        };
+
+        annotate(&mut schema, map);
+        Ok(schema)
+    }
+
+    fn convert_named(
+        &mut self,
+        type_: &str,
+        map: &Map<String, Value>,
+        namespace: &str,
+    ) -> Result<Value, Error> {
+        let name = map
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("`name` is required for named types"))?;
+
+        let namespace = map
+            .get("namespace")
+            .and_then(Value::as_str)
+            .unwrap_or(namespace);
+
+        let fullname = fullname(name, namespace);
+        let namespace = fullname.rsplit_once('.').map_or("", |(ns, _)| ns);
+
+        // Registered before fields are converted to allow recursive references.
+        self.definitions.insert(fullname.clone(), json!({}));
+
+        let mut schema = match type_ {
+            "enum" => {
+                let symbols = map
+                    .get("symbols")
+                    .ok_or_else(|| invalid("`symbols` is required"))?;
+                json!({"type": "string", "enum": symbols})
+            }
+            "fixed" => convert_fixed(map)?,
+            _ => self.convert_record(map, namespace)?,
+        };
+
+        annotate(&mut schema, map);
+        self.definitions.insert(fullname, schema.clone());
+
+        Ok(schema)
+    }
+
+    fn convert_record(
+        &mut self,
+        map: &Map<String, Value>,
+        namespace: &str,
+    ) -> Result<Value, Error> {
+        let fields = map
+            .get("fields")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("`fields` is required for records"))?;
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+
+        for field in fields {
+            let name = field
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("`name` is required for fields"))?;
+
+            let type_ = field
+                .get("type")
+                .ok_or_else(|| invalid("`type` is required for fields"))?;
+
+            let mut schema = self.convert(type_, namespace)?;
+
+            // Readers fill in missing fields with defaults.
+            match field.get("default") {
+                Some(default) => {
+                    if let Value::Object(ref mut map) = schema {
+                        map.insert("default".into(), default.clone());
+                    }
+                }
+                None => required.push(Value::from(name)),
+            }
+
+            if let (Some(doc), Value::Object(ref mut map)) = (field.get("doc"), &mut schema) {
+                map.insert("description".into(), doc.clone());
+            }
+
+            properties.insert(name.into(), schema);
+        }
+
+        let mut schema = json!({"type": "object", "properties": properties});
+
+        if !required.is_empty() {
+            schema["required"] = Value::Array(required);
+        }
+
+        Ok(schema)
+    }
+}
+
+/// Bytes of fixed types are written as strings with a character per byte.
+fn convert_fixed(map: &Map<String, Value>) -> Result<Value, Error> {
+    let size = map
+        .get("size")
+        .ok_or_else(|| invalid("`size` is required"))?;
+    Ok(json!({"type": "string", "minLength": size, "maxLength": size}))
+}
+
+/// Copies documentation of the type and notes its logical type.
+fn annotate(schema: &mut Value, map: &Map<String, Value>) {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(doc) = map.get("doc") {
+        schema.insert("description".into(), doc.clone());
+    }
+
+    if let Some(logical) = map.get("logicalType").and_then(Value::as_str) {
+        schema.insert(
+            "$comment".into(),
+            format!("logicalType: {}", logical).into(),
+        );
+    }
+}
+
+fn fullname(name: &str, namespace: &str) -> String {
+    if name.contains('.') || namespace.is_empty() {
+        name.into()
+    } else {
+        format!("{}.{}", namespace, name)
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidSchema(format!("invalid Avro schema: {}", reason))
+}
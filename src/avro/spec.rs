@@ -0,0 +1,77 @@
+use super::*;
+
+fn convert_value(avro: Value) -> Value {
+    Converter::default().convert(&avro, "").unwrap()
+}
+
+#[test]
+fn it_should_bound_ints() {
+    assert_eq!(
+        convert_value(json!("int")),
+        json!({"type": "integer", "minimum": -2147483648i64, "maximum": 2147483647})
+    );
+}
+
+#[test]
+fn it_should_require_fields_without_defaults() {
+    let record = json!({
+        "type": "record",
+        "name": "User",
+        "fields": [
+            {"name": "id", "type": "string"},
+            {"name": "age", "type": ["null", "int"], "default": null},
+        ],
+    });
+
+    let schema = convert_value(record);
+    assert_eq!(schema["required"], json!(["id"]));
+    assert_eq!(schema["properties"]["age"]["default"], json!(null));
+}
+
+#[test]
+fn it_should_resolve_names_in_namespaces() {
+    let record = json!({
+        "type": "record",
+        "name": "Node",
+        "namespace": "com.example",
+        "fields": [
+            {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B"]}},
+            {"name": "other", "type": "Kind"},
+            {"name": "same", "type": "com.example.Kind"},
+        ],
+    });
+
+    let schema = convert_value(record);
+    let reference = json!({"$ref": "#/definitions/com.example.Kind"});
+    assert_eq!(schema["properties"]["other"], reference);
+    assert_eq!(schema["properties"]["same"], reference);
+}
+
+#[test]
+fn it_should_keep_underlying_types_of_logical_types() {
+    let timestamp = json!({"type": "long", "logicalType": "timestamp-millis"});
+    assert_eq!(
+        convert_value(timestamp),
+        json!({
+            "type": "integer",
+            "minimum": i64::MIN,
+            "maximum": i64::MAX,
+            "$comment": "logicalType: timestamp-millis",
+        })
+    );
+
+    let decimal = json!({"type": "bytes", "logicalType": "decimal", "precision": 4});
+    assert_eq!(convert_value(decimal)["type"], json!("string"));
+
+    let duration =
+        json!({"type": "fixed", "name": "Duration", "size": 12, "logicalType": "duration"});
+    assert_eq!(convert_value(duration)["maxLength"], json!(12));
+}
+
+#[test]
+fn it_should_fail_on_unknown_names() {
+    match Converter::default().convert(&json!("Missing"), "") {
+        Err(Error::UnresolvedRef(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
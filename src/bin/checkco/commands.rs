@@ -0,0 +1,290 @@
+//! Commands other than checking, selected by the first argument.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use checkco::{Error, Mode, Options, Schema, Verdict};
+use serde_json::Value;
+use structopt::StructOpt;
+
+use output::{self, Format};
+use {exit_code, json, load, EXIT_COMPATIBLE, EXIT_INCOMPATIBLE, EXIT_INVALID_INPUT};
+
+/// Normalizes the schema into a canonical `anyOf` of units.
+///
+/// References are inlined, `allOf`s are merged and subsumed variants are removed.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "checkco normalize")]
+struct Normalize {
+    #[structopt(parse(from_os_str))]
+    schema: PathBuf,
+    /// The maximum number of units the schema can be spread into.
+    #[structopt(long = "max-units", default_value = "10000")]
+    max_units: usize,
+}
+
+/// Builds a schema matching values valid against all the schemas.
+///
+/// E.g. the payloads that all consumers accept.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "checkco intersect")]
+struct Intersect {
+    #[structopt(parse(from_os_str), required = true, min_values = 2)]
+    schemas: Vec<PathBuf>,
+}
+
+/// Builds a schema matching values valid against any of the schemas.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "checkco union")]
+struct Union {
+    #[structopt(parse(from_os_str), required = true, min_values = 2)]
+    schemas: Vec<PathBuf>,
+}
+
+/// Builds the tightest schema with a unit per type matching values of all the schemas.
+///
+/// E.g. the payloads that every provider version may send.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "checkco join")]
+struct Join {
+    #[structopt(parse(from_os_str), required = true, min_values = 1)]
+    schemas: Vec<PathBuf>,
+}
+
+/// Describes values the derived schema accepts, but the base one doesn't.
+///
+/// Prints the schema of the difference together with example instances.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "checkco difference")]
+struct Difference {
+    #[structopt(parse(from_os_str))]
+    derived: PathBuf,
+    #[structopt(parse(from_os_str))]
+    base: PathBuf,
+}
+
+/// Infers a schema from sample instances and checks it against the base schema, if given.
+///
+/// Samples are JSON documents, or JSON Lines in `.jsonl` files.
+/// The inferred schema is printed, unless it's written to a file or checked.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "checkco infer")]
+struct Infer {
+    #[structopt(parse(from_os_str), required = true, min_values = 1)]
+    samples: Vec<PathBuf>,
+    /// The schema the inferred one must be compatible with.
+    #[structopt(long = "base", parse(from_os_str))]
+    base: Option<PathBuf>,
+    /// Writes the inferred schema to the file, which SARIF results then point into.
+    #[structopt(long = "output", parse(from_os_str))]
+    output: Option<PathBuf>,
+    /// Checking mode: strict, request or response.
+    #[structopt(long = "mode", default_value = "strict")]
+    mode: Mode,
+    /// Output format of the check: text, json or sarif.
+    #[structopt(long = "format", default_value = "text")]
+    format: Format,
+    /// Strings and integers with at most this many distinct values become enums.
+    #[structopt(long = "max-enum", default_value = "8")]
+    max_enum: usize,
+}
+
+/// Runs the command if the arguments start with its name.
+pub fn run(args: &[OsString]) -> Option<i32> {
+    // The command name takes place of the binary name.
+    let args = args.iter().skip(1);
+
+    match args.clone().next().and_then(|arg| arg.to_str()) {
+        Some("normalize") => Some(normalize(&Normalize::from_iter(args))),
+        Some("intersect") => Some(combine(&Intersect::from_iter(args).schemas, true)),
+        Some("union") => Some(combine(&Union::from_iter(args).schemas, false)),
+        Some("infer") => Some(infer(&Infer::from_iter(args))),
+        Some("join") => Some(join(&Join::from_iter(args).schemas)),
+        Some("difference") => Some(difference(&Difference::from_iter(args))),
+        _ => None,
+    }
+}
+
+fn normalize(options: &Normalize) -> i32 {
+    let schema = match read(&options.schema) {
+        Ok(schema) => schema,
+        Err(code) => return code,
+    };
+
+    let options = Options {
+        max_units: options.max_units,
+        ..Options::default()
+    };
+
+    match checkco::normalize(schema, &options) {
+        Ok(normalized) => {
+            println!("{:#}", normalized);
+            0
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit_code(&err)
+        }
+    }
+}
+
+fn infer(options: &Infer) -> i32 {
+    let mut samples = Vec::new();
+
+    for path in &options.samples {
+        match read_samples(path) {
+            Ok(values) => samples.extend(values),
+            Err(code) => return code,
+        }
+    }
+
+    let inferred = checkco::infer(&samples, options.max_enum);
+    let source = format!("{:#}", inferred);
+
+    if let Some(ref output) = options.output {
+        if let Err(err) = fs::write(output, &source) {
+            eprintln!("error: {}: {}", output.display(), err);
+            return EXIT_INVALID_INPUT;
+        }
+    }
+
+    let base = match options.base {
+        Some(ref base) => base,
+        None if options.output.is_some() => return 0,
+        None => {
+            println!("{}", source);
+            return 0;
+        }
+    };
+
+    let base = match read(base) {
+        Ok(base) => base,
+        Err(code) => return code,
+    };
+
+    let check_options = Options {
+        mode: options.mode,
+        ..Options::default()
+    };
+
+    let result = serde_json::from_value(inferred)
+        .map_err(Error::from)
+        .and_then(|derived| checkco::check_with(derived, base, &check_options));
+
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return exit_code(&err);
+        }
+    };
+
+    // Mismatches can only be located in the inferred schema once it's written.
+    let path = options.output.as_deref();
+    println!(
+        "{}",
+        output::render(options.format, &result, path, Some(&source))
+    );
+
+    match result {
+        Verdict::Success => EXIT_COMPATIBLE,
+        Verdict::Failure(_) => EXIT_INCOMPATIBLE,
+    }
+}
+
+/// Reads sample instances: a document per file, or a document per line of `.jsonl` files.
+fn read_samples(path: &Path) -> Result<Vec<Value>, i32> {
+    let source = load(path)?;
+
+    if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+        return Ok(vec![json(path, &source)?]);
+    }
+
+    source
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| json(path, line))
+        .collect()
+}
+
+fn join(paths: &[PathBuf]) -> i32 {
+    let mut schemas = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        match read(path) {
+            Ok(schema) => schemas.push(schema),
+            Err(code) => return code,
+        }
+    }
+
+    match checkco::join(schemas) {
+        Ok(joined) => {
+            println!("{:#}", joined);
+            0
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit_code(&err)
+        }
+    }
+}
+
+fn difference(options: &Difference) -> i32 {
+    let (derived, base) = match (read(&options.derived), read(&options.base)) {
+        (Ok(derived), Ok(base)) => (derived, base),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+
+    match checkco::difference(derived, base) {
+        Ok(diff) => {
+            let output = json!({
+                "schema": diff.schema,
+                "examples": diff.examples,
+                "exact": diff.exact,
+            });
+
+            println!("{:#}", output);
+            0
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit_code(&err)
+        }
+    }
+}
+
+fn combine(paths: &[PathBuf], intersect: bool) -> i32 {
+    let mut schemas = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        match read(path) {
+            Ok(schema) => schemas.push(schema),
+            Err(code) => return code,
+        }
+    }
+
+    let combined = if intersect {
+        checkco::intersect_all(schemas)
+    } else {
+        checkco::union_all(schemas)
+    };
+
+    match combined {
+        Ok(combined) => {
+            println!("{:#}", combined);
+            0
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit_code(&err)
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<Schema, i32> {
+    load(path)?.parse().map_err(|err| {
+        eprintln!("error: {}: {}", path.display(), err);
+        exit_code(&err)
+    })
+}
@@ -0,0 +1,170 @@
+//! Best-effort mapping of JSON Pointers to lines of the source document.
+//!
+//! Mismatches refer to normalized schemas, so some segments (e.g. ones inlined from `allOf`)
+//! don't exist in the source. In this case the deepest existing location is used.
+
+#[cfg(test)]
+mod spec;
+
+pub fn line(source: &str, pointer: &str) -> usize {
+    let segments = pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>();
+
+    let mut scanner = Scanner {
+        bytes: source.as_bytes(),
+        pos: 0,
+    };
+
+    let offset = scanner.find(&segments);
+    source[..offset].matches('\n').count() + 1
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn find(&mut self, segments: &[String]) -> usize {
+        self.skip_ws();
+        let start = self.pos;
+
+        let segment = match segments.first() {
+            Some(segment) => segment,
+            None => return start,
+        };
+
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+
+                while self.next_member() {
+                    let key = self.string();
+                    self.skip_ws();
+                    self.eat(b':');
+
+                    if key == *segment {
+                        return self.find(&segments[1..]);
+                    }
+
+                    self.skip_value();
+                }
+
+                start
+            }
+            Some(b'[') => {
+                let index = match segment.parse::<usize>() {
+                    Ok(index) => index,
+                    Err(_) => return start,
+                };
+
+                self.pos += 1;
+                let mut i = 0;
+
+                while self.next_member() {
+                    if i == index {
+                        return self.find(&segments[1..]);
+                    }
+
+                    self.skip_value();
+                    i += 1;
+                }
+
+                start
+            }
+            _ => start,
+        }
+    }
+
+    /// Moves to the next member of an object or an array.
+    fn next_member(&mut self) -> bool {
+        self.skip_ws();
+        self.eat(b',');
+        self.skip_ws();
+
+        match self.peek() {
+            Some(b'}') | Some(b']') | None => false,
+            Some(_) => true,
+        }
+    }
+
+    fn skip_value(&mut self) {
+        self.skip_ws();
+
+        match self.peek() {
+            Some(b'"') => {
+                self.string();
+            }
+            Some(b'{') | Some(b'[') => {
+                let mut depth = 0;
+
+                while let Some(byte) = self.peek() {
+                    match byte {
+                        b'"' => {
+                            self.string();
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+
+                    self.pos += 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                while let Some(byte) = self.peek() {
+                    match byte {
+                        b',' | b'}' | b']' => break,
+                        _ => self.pos += 1,
+                    }
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let mut value = Vec::new();
+        self.eat(b'"');
+
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    if let Some(escaped) = self.peek() {
+                        value.push(escaped);
+                        self.pos += 1;
+                    }
+                }
+                _ => value.push(byte),
+            }
+        }
+
+        String::from_utf8_lossy(&value).into_owned()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).cloned()
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+const SOURCE: &str = r#"{
+  "type": "object",
+  "properties": {
+    "a/b": {"type": "string"},
+    "c": {
+      "description": "not a {key: \"c\"}",
+      "maximum": 10
+    },
+    "d": {
+      "items": [
+        {"type": "null"},
+        {"type": "integer"}
+      ]
+    }
+  }
+}"#;
+
+#[test]
+fn it_should_locate_nested_keys() {
+    assert_eq!(line(SOURCE, ""), 1);
+    assert_eq!(line(SOURCE, "/type"), 2);
+    assert_eq!(line(SOURCE, "/properties/c/maximum"), 7);
+}
+
+#[test]
+fn it_should_locate_array_items() {
+    assert_eq!(line(SOURCE, "/properties/d/items/1"), 12);
+    assert_eq!(line(SOURCE, "/properties/d/items/1/type"), 12);
+}
+
+#[test]
+fn it_should_unescape_segments() {
+    assert_eq!(line(SOURCE, "/properties/a~1b/type"), 4);
+}
+
+#[test]
+fn it_should_fall_back_to_the_deepest_existing_location() {
+    assert_eq!(line(SOURCE, "/properties/c/minimum"), 5);
+    assert_eq!(line(SOURCE, "/properties/d/items/5"), 10);
+    assert_eq!(line(SOURCE, "/allOf/0"), 1);
+}
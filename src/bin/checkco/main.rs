@@ -0,0 +1,211 @@
+extern crate checkco;
+extern crate structopt;
+#[macro_use]
+extern crate serde_json;
+
+mod commands;
+mod locate;
+mod output;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use checkco::{Compatibility, Error, Mode, Options, Schema, Verdict};
+use serde_json::Value;
+use structopt::StructOpt;
+
+use output::Format;
+
+const EXIT_COMPATIBLE: i32 = 0;
+const EXIT_INCOMPATIBLE: i32 = 1;
+const EXIT_INVALID_INPUT: i32 = 2;
+const EXIT_UNSUPPORTED: i32 = 3;
+
+#[derive(Debug, StructOpt)]
+#[structopt(after_help = "EXIT CODES:
+    0    the derived schema is compatible with the base one
+    1    the derived schema is incompatible with the base one
+    2    a schema cannot be read, parsed or resolved
+    3    a schema uses unsupported features or is too complex
+
+COMMANDS:
+    normalize <schema>              prints the schema as a canonical anyOf of units
+    intersect <schema> <schema>...  prints a schema of values valid against all schemas
+    union <schema> <schema>...      prints a schema of values valid against any schema
+    join <schema>...                prints the tightest schema with a unit per type matching all schemas
+    difference <derived> <base>     prints a schema and examples of newly accepted values
+    infer <sample>... [--base <schema>]
+                                    infers a schema from samples and checks it against the base")]
+struct Cli {
+    #[structopt(parse(from_os_str))]
+    derived: PathBuf,
+    #[structopt(parse(from_os_str))]
+    base: PathBuf,
+    /// Checking mode of schemas, not documents: strict (default), request or response.
+    #[structopt(long = "mode", raw(conflicts_with_all = r#"&["openapi", "asyncapi"]"#))]
+    mode: Option<Mode>,
+    /// Output format: text, json or sarif.
+    #[structopt(long = "format", default_value = "text")]
+    format: Format,
+    /// The maximum number of units a schema can be spread into.
+    #[structopt(long = "max-units", default_value = "10000")]
+    max_units: usize,
+    /// Treat inputs as OpenAPI 3.0/3.1 documents instead of schemas.
+    #[structopt(long = "openapi")]
+    openapi: bool,
+    /// Treat inputs as AsyncAPI 2.x/3.x documents instead of schemas.
+    #[structopt(long = "asyncapi", conflicts_with = "openapi")]
+    asyncapi: bool,
+    /// Compatibility of AsyncAPI messages: backward, forward or full.
+    #[structopt(long = "compatibility", default_value = "backward")]
+    compatibility: Compatibility,
+    /// The message to check in `.proto` files.
+    #[structopt(long = "message")]
+    message: Option<String>,
+    /// The declared type to check in `.ts` files.
+    #[structopt(long = "type")]
+    type_: Option<String>,
+}
+
+fn main() {
+    let args = env::args_os().collect::<Vec<_>>();
+
+    if let Some(code) = commands::run(&args) {
+        process::exit(code);
+    }
+
+    let options = Cli::from_iter(args);
+    process::exit(run(&options));
+}
+
+fn run(options: &Cli) -> i32 {
+    // Keep the source to point SARIF results at the offending lines.
+    let source = match load(&options.derived) {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
+
+    let base_source = match load(&options.base) {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
+
+    let check_options = Options {
+        mode: options.mode.unwrap_or_default(),
+        max_units: options.max_units,
+    };
+
+    let result = if options.openapi || options.asyncapi {
+        let derived = json(&options.derived, &source);
+        let base = json(&options.base, &base_source);
+
+        let (derived, base) = match (derived, base) {
+            (Ok(derived), Ok(base)) => (derived, base),
+            (Err(code), _) | (_, Err(code)) => return code,
+        };
+
+        if options.openapi {
+            checkco::check_openapi(&derived, &base, &check_options)
+        } else {
+            checkco::check_asyncapi(&derived, &base, options.compatibility, &check_options)
+        }
+    } else {
+        let derived = parse(&options.derived, &source, options);
+        let base = parse(&options.base, &base_source, options);
+
+        match (derived, base) {
+            (Ok(derived), Ok(base)) => checkco::check_with(derived, base, &check_options),
+            (Err(code), _) | (_, Err(code)) => return code,
+        }
+    };
+
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return exit_code(&err);
+        }
+    };
+
+    // Mismatches of converted schemas point into the conversion, not into the source.
+    let documents = options.openapi || options.asyncapi;
+    let located = if documents || !is_converted(&options.derived) {
+        Some(source.as_str())
+    } else {
+        None
+    };
+
+    let output = output::render(options.format, &result, Some(&options.derived), located);
+    println!("{}", output);
+
+    match result {
+        Verdict::Success => EXIT_COMPATIBLE,
+        Verdict::Failure(_) => EXIT_INCOMPATIBLE,
+    }
+}
+
+fn load(path: &Path) -> Result<String, i32> {
+    fs::read_to_string(path).map_err(|err| {
+        eprintln!("error: {}: {}", path.display(), err);
+        EXIT_INVALID_INPUT
+    })
+}
+
+fn json(path: &Path, source: &str) -> Result<Value, i32> {
+    serde_json::from_str(source).map_err(|err| {
+        eprintln!("error: {}: {}", path.display(), err);
+        EXIT_INVALID_INPUT
+    })
+}
+
+fn is_converted(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("avsc") | Some("proto") | Some("ts")
+    )
+}
+
+/// Parses a schema, converting it by the extension: `.avsc` for Avro, `.proto` for Protobuf
+/// and `.ts` for TypeScript.
+fn parse(path: &Path, source: &str, options: &Cli) -> Result<Schema, i32> {
+    let schema = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("avsc") => checkco::from_avro(&json(path, source)?),
+        Some("proto") => match options.message {
+            Some(ref message) => checkco::from_proto(source, message),
+            None => {
+                eprintln!(
+                    "error: {}: --message is required for .proto files",
+                    path.display()
+                );
+                return Err(EXIT_INVALID_INPUT);
+            }
+        },
+        Some("ts") => match options.type_ {
+            Some(ref type_) => checkco::from_typescript(source, type_),
+            None => {
+                eprintln!(
+                    "error: {}: --type is required for .ts files",
+                    path.display()
+                );
+                return Err(EXIT_INVALID_INPUT);
+            }
+        },
+        _ => source.parse(),
+    };
+
+    schema.map_err(|err| {
+        eprintln!("error: {}: {}", path.display(), err);
+        exit_code(&err)
+    })
+}
+
+fn exit_code(err: &Error) -> i32 {
+    match err {
+        Error::InvalidSchema(_) | Error::InvalidRegex(_) | Error::UnresolvedRef(_) => {
+            EXIT_INVALID_INPUT
+        }
+        Error::TooManyUnits(_) | Error::Unsupported(_) => EXIT_UNSUPPORTED,
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::str::FromStr;
+
+use checkco::{Mismatch, Verdict};
+use serde_json::Value;
+
+use locate;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "sarif" => Ok(Format::Sarif),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+/// Renders the verdict on the derived schema at `path`, if it's stored in a file.
+///
+/// SARIF results point at lines of the `source`, if mismatches refer to it.
+pub fn render(
+    format: Format,
+    verdict: &Verdict,
+    path: Option<&Path>,
+    source: Option<&str>,
+) -> String {
+    let mismatches = match verdict {
+        Verdict::Success => &[][..],
+        Verdict::Failure(mismatches) => &mismatches[..],
+    };
+
+    match format {
+        Format::Text => render_text(verdict, mismatches),
+        Format::Json => render_json(verdict, mismatches).to_string(),
+        Format::Sarif => render_sarif(mismatches, path, source).to_string(),
+    }
+}
+
+fn render_text(verdict: &Verdict, mismatches: &[Mismatch]) -> String {
+    let mut text = match verdict {
+        Verdict::Success => ">> Success".to_owned(),
+        Verdict::Failure(_) => ">> Failure".to_owned(),
+    };
+
+    for mismatch in mismatches {
+        text.push_str(&format!(
+            "\n   {}: {}",
+            display(&mismatch.pointer),
+            mismatch.keyword
+        ));
+    }
+
+    text
+}
+
+fn render_json(verdict: &Verdict, mismatches: &[Mismatch]) -> Value {
+    let incompatibilities = mismatches
+        .iter()
+        .map(|m| json!({"pointer": m.pointer, "keyword": m.keyword}))
+        .collect::<Vec<_>>();
+
+    json!({
+        "compatible": matches!(verdict, Verdict::Success),
+        "incompatibilities": incompatibilities,
+    })
+}
+
+fn render_sarif(mismatches: &[Mismatch], path: Option<&Path>, source: Option<&str>) -> Value {
+    let rules = mismatches
+        .iter()
+        .map(|m| m.keyword)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|keyword| {
+            json!({
+                "id": keyword,
+                "shortDescription": {
+                    "text": format!("Incompatible `{}` keyword", keyword),
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = mismatches
+        .iter()
+        .map(|m| {
+            let mut location = json!({
+                "logicalLocations": [{ "fullyQualifiedName": display(&m.pointer) }],
+            });
+
+            if let Some(path) = path {
+                let mut physical = json!({ "artifactLocation": { "uri": path.to_string_lossy() } });
+
+                if let Some(source) = source {
+                    physical["region"] =
+                        json!({ "startLine": locate::line(source, &keyword_pointer(m)) });
+                }
+
+                location["physicalLocation"] = physical;
+            }
+
+            json!({
+                "ruleId": m.keyword,
+                "level": "error",
+                "message": {
+                    "text": format!(
+                        "`{}` at {} is incompatible with the base schema",
+                        m.keyword,
+                        display(&m.pointer),
+                    ),
+                },
+                "locations": [location],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "checkco",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn keyword_pointer(mismatch: &Mismatch) -> String {
+    format!("{}/{}", mismatch.pointer, mismatch.keyword)
+}
+
+fn display(pointer: &str) -> &str {
+    if pointer.is_empty() {
+        "/"
+    } else {
+        pointer
+    }
+}
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+/// Defines which side has to understand messages of the other one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// New consumers read messages written by old producers.
+    #[default]
+    Backward,
+    /// Old consumers read messages written by new producers.
+    Forward,
+    /// Both backward and forward.
+    Full,
+}
+
+impl Compatibility {
+    pub(crate) fn is_backward(self) -> bool {
+        self != Compatibility::Forward
+    }
+
+    pub(crate) fn is_forward(self) -> bool {
+        self != Compatibility::Backward
+    }
+}
+
+impl FromStr for Compatibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Compatibility, String> {
+        match s {
+            "backward" => Ok(Compatibility::Backward),
+            "forward" => Ok(Compatibility::Forward),
+            "full" => Ok(Compatibility::Full),
+            _ => Err(format!("unknown compatibility: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Differences of schemas: values valid against one schema, but not against another.
+//!
+//! The difference is the meet of the first schema with the complement of the second.
+//! Units aren't closed under complement, e.g. there is no unit for strings not matching
+//! a pattern, nor under meet, e.g. of two `contains`, so such cases are left out
+//! and the difference is marked inexact.
+
+use std::iter;
+
+use serde_json::Value;
+
+use error::Error;
+use matcher::vacuous;
+use merger;
+use schema::{RcStr, Type};
+use unit::{Point, RcUnit, Unit, Variants};
+
+#[cfg(test)]
+mod spec;
+
+// `integer` is omitted, because `number` covers it.
+const TYPES: [Type; 6] = [
+    Type::Null,
+    Type::Boolean,
+    Type::Object,
+    Type::Array,
+    Type::Number,
+    Type::String,
+];
+
+/// Units of values valid against the derived units, but not against the base ones.
+#[derive(Debug)]
+pub struct Subtraction {
+    pub units: Variants,
+    /// Whether the units cover the whole difference rather than a part of it.
+    pub exact: bool,
+}
+
+/// Subtracts the base disjunction from the derived one.
+pub fn subtract(
+    derived: &[RcUnit],
+    base: &[RcUnit],
+    max_units: usize,
+) -> Result<Subtraction, Error> {
+    let mut complement = Complement {
+        max_units,
+        exact: true,
+    };
+
+    let rest = complement.variants(base)?;
+    let units = complement.meet(derived, &rest)?;
+
+    Ok(Subtraction {
+        units,
+        exact: complement.exact,
+    })
+}
+
+struct Complement {
+    max_units: usize,
+    exact: bool,
+}
+
+impl Complement {
+    /// The complement of a disjunction is the meet of complements of its units.
+    fn variants(&mut self, variants: &[RcUnit]) -> Result<Variants, Error> {
+        let mut res = vec![RcUnit::from(Unit::default())];
+
+        for unit in variants {
+            let complement = self.unit(unit)?;
+            res = self.meet(&res, &complement)?;
+        }
+
+        Ok(res)
+    }
+
+    /// Intersects the disjunctions, leaving out intersections that units can't express.
+    fn meet(&mut self, a: &[RcUnit], b: &[RcUnit]) -> Result<Variants, Error> {
+        let mut res = Vec::new();
+
+        for unit in a {
+            for other in b {
+                let mut merged = Unit::clone(unit);
+
+                match merger::merge(&mut merged, other) {
+                    Ok(true) => res.extend(clean(merged.into())),
+                    Ok(false) => {}
+                    Err(Error::Unsupported(_)) => self.exact = false,
+                    Err(err) => return Err(err),
+                }
+
+                if res.len() > self.max_units {
+                    return Err(Error::TooManyUnits(self.max_units));
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Each case of the complement violates one restriction of the unit.
+    fn unit(&mut self, unit: &Unit) -> Result<Variants, Error> {
+        let mut res = Vec::new();
+
+        self.type_(unit, &mut res);
+        self.number(unit, &mut res);
+        self.string(unit, &mut res);
+        self.array(unit, &mut res)?;
+        self.object(unit, &mut res)?;
+
+        Ok(res.into_iter().map(RcUnit::from).collect())
+    }
+
+    fn type_(&mut self, unit: &Unit, res: &mut Vec<Unit>) {
+        let type_ = unit
+            .type_
+            .or_else(|| unit.const_.as_ref().map(|c| type_of(c)));
+
+        let type_ = match type_ {
+            Some(type_) => type_,
+            None => return,
+        };
+
+        // Non-integral numbers have no unit.
+        if type_ == Type::Integer {
+            self.exact = false;
+        }
+
+        for &other in TYPES.iter() {
+            if other != type_ && !(type_ == Type::Integer && other == Type::Number) {
+                res.push(typed(other));
+            }
+        }
+
+        match unit.const_.as_deref() {
+            None | Some(Value::Null) => {}
+            Some(Value::Bool(b)) => res.push(Unit {
+                const_: Some((!b).into()),
+                ..Unit::default()
+            }),
+            Some(_) => self.exact = false,
+        }
+    }
+
+    fn number(&mut self, unit: &Unit, res: &mut Vec<Unit>) {
+        if let Some(max) = unit.maximum {
+            res.push(Unit {
+                minimum: Some(complement(max)),
+                ..typed(Type::Number)
+            });
+        }
+
+        if let Some(min) = unit.minimum {
+            res.push(Unit {
+                maximum: Some(complement(min)),
+                ..typed(Type::Number)
+            });
+        }
+
+        if unit.multiple_of.is_some() {
+            self.exact = false;
+        }
+    }
+
+    fn string(&mut self, unit: &Unit, res: &mut Vec<Unit>) {
+        if let Some(max) = unit.max_length {
+            res.push(Unit {
+                min_length: Some(max + 1),
+                ..typed(Type::String)
+            });
+        }
+
+        if let Some(min) = unit.min_length.filter(|&min| min > 0) {
+            res.push(Unit {
+                max_length: Some(min - 1),
+                ..typed(Type::String)
+            });
+        }
+
+        if !unit.pattern.is_empty() || unit.format.is_some() {
+            self.exact = false;
+        }
+    }
+
+    fn array(&mut self, unit: &Unit, res: &mut Vec<Unit>) -> Result<(), Error> {
+        if let Some(max) = unit.max_items {
+            res.push(Unit {
+                min_items: Some(max + 1),
+                ..typed(Type::Array)
+            });
+        }
+
+        if let Some(min) = unit.min_items.filter(|&min| min > 0) {
+            res.push(Unit {
+                max_items: Some(min - 1),
+                ..typed(Type::Array)
+            });
+        }
+
+        // Some item violates `items`.
+        if let (Some(items), true) = (&unit.items, unit.tuple.is_empty()) {
+            let contains = self.variants(items)?;
+
+            if !contains.is_empty() {
+                res.push(Unit {
+                    contains: Some(contains),
+                    ..typed(Type::Array)
+                });
+            }
+        }
+
+        // No item matches `contains`.
+        if let Some(ref contains) = unit.contains {
+            res.push(Unit {
+                items: Some(self.variants(contains)?),
+                ..typed(Type::Array)
+            });
+        }
+
+        if !unit.tuple.is_empty() || unit.unique_items {
+            self.exact = false;
+        }
+
+        Ok(())
+    }
+
+    fn object(&mut self, unit: &Unit, res: &mut Vec<Unit>) -> Result<(), Error> {
+        if let Some(max) = unit.max_properties {
+            res.push(Unit {
+                min_properties: Some(max + 1),
+                ..typed(Type::Object)
+            });
+        }
+
+        if let Some(min) = unit.min_properties.filter(|&min| min > 0) {
+            res.push(Unit {
+                max_properties: Some(min - 1),
+                ..typed(Type::Object)
+            });
+        }
+
+        for prop in &unit.required {
+            res.push(Unit {
+                properties: iter::once((prop.clone(), Vec::new())).collect(),
+                ..typed(Type::Object)
+            });
+        }
+
+        // The property is present, but violates its variants.
+        for (prop, variants) in &unit.properties {
+            let variants = self.variants(variants)?;
+
+            if !variants.is_empty() {
+                res.push(Unit {
+                    required: iter::once(RcStr::clone(prop)).collect(),
+                    properties: iter::once((prop.clone(), variants)).collect(),
+                    ..typed(Type::Object)
+                });
+            }
+        }
+
+        if !unit.pattern_props.is_empty()
+            || unit.additional_props.is_some()
+            || unit.property_names.is_some()
+        {
+            self.exact = false;
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes empty nested units, dropping the unit if it becomes empty itself.
+fn clean(unit: RcUnit) -> Option<RcUnit> {
+    let mut cleaned = Unit::clone(&unit);
+
+    for variants in cleaned.nested_mut() {
+        *variants = variants.drain(..).filter_map(clean).collect();
+    }
+
+    if empty(&cleaned) || !merger::satisfiable(&cleaned) {
+        None
+    } else {
+        Some(cleaned.into())
+    }
+}
+
+/// Checks whether contradicting bounds leave no values, although merging has kept the unit.
+fn empty(unit: &Unit) -> bool {
+    fn crossed(max: Option<u32>, min: Option<u32>) -> bool {
+        matches!((max, min), (Some(max), Some(min)) if max < min)
+    }
+
+    vacuous(unit)
+        || crossed(unit.max_length, unit.min_length)
+        || crossed(unit.max_items, unit.min_items)
+        || crossed(unit.max_properties, unit.min_properties)
+}
+
+fn type_of(value: &Value) -> Type {
+    match value {
+        Value::Null => Type::Null,
+        Value::Bool(_) => Type::Boolean,
+        Value::Number(n) if n.is_f64() => Type::Number,
+        Value::Number(_) => Type::Integer,
+        Value::String(_) => Type::String,
+        Value::Array(_) => Type::Array,
+        Value::Object(_) => Type::Object,
+    }
+}
+
+fn typed(type_: Type) -> Unit {
+    Unit {
+        type_: Some(type_),
+        ..Unit::default()
+    }
+}
+
+fn complement(point: Point) -> Point {
+    Point {
+        value: point.value,
+        inclusive: !point.inclusive,
+    }
+}
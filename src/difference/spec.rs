@@ -0,0 +1,180 @@
+use super::*;
+
+use schema::RcStr;
+
+fn unit(unit: Unit) -> RcUnit {
+    unit.into()
+}
+
+fn typed_unit(type_: Type) -> RcUnit {
+    typed(type_).into()
+}
+
+fn run(derived: Vec<RcUnit>, base: Vec<RcUnit>) -> Subtraction {
+    subtract(&derived, &base, 1000).unwrap()
+}
+
+#[test]
+fn it_should_subtract_types() {
+    let res = run(
+        vec![typed_unit(Type::String), typed_unit(Type::Null)],
+        vec![typed_unit(Type::String)],
+    );
+
+    assert_eq!(res.units, vec![typed_unit(Type::Null)]);
+    assert!(res.exact);
+}
+
+#[test]
+fn it_should_subtract_bounds() {
+    let derived = unit(Unit {
+        maximum: Some(Point::inc(100.)),
+        ..typed(Type::Number)
+    });
+
+    let base = unit(Unit {
+        maximum: Some(Point::exc(10.)),
+        ..typed(Type::Number)
+    });
+
+    let res = run(vec![derived], vec![base]);
+
+    assert_eq!(
+        res.units,
+        vec![unit(Unit {
+            maximum: Some(Point::inc(100.)),
+            minimum: Some(Point::inc(10.)),
+            ..typed(Type::Number)
+        })]
+    );
+    assert!(res.exact);
+}
+
+#[test]
+fn it_should_be_empty_for_subtypes() {
+    let derived = unit(Unit {
+        max_length: Some(5),
+        ..typed(Type::String)
+    });
+
+    let base = unit(Unit {
+        max_length: Some(10),
+        ..typed(Type::String)
+    });
+
+    assert!(run(vec![derived], vec![base]).units.is_empty());
+}
+
+#[test]
+fn it_should_subtract_required_properties() {
+    let derived = typed_unit(Type::Object);
+    let base = unit(Unit {
+        required: vec![RcStr::from("id")].into_iter().collect(),
+        ..typed(Type::Object)
+    });
+
+    let res = run(vec![derived], vec![base]);
+
+    assert_eq!(
+        res.units,
+        vec![unit(Unit {
+            properties: vec![(RcStr::from("id"), Vec::new())].into_iter().collect(),
+            ..typed(Type::Object)
+        })]
+    );
+}
+
+#[test]
+fn it_should_subtract_nested_properties() {
+    let prop = |type_| {
+        unit(Unit {
+            properties: vec![(RcStr::from("id"), vec![typed_unit(type_)])]
+                .into_iter()
+                .collect(),
+            ..typed(Type::Object)
+        })
+    };
+
+    let derived = prop(Type::Number);
+    let base = prop(Type::Integer);
+
+    let res = run(vec![derived], vec![base]);
+
+    // Non-integral numbers have no unit.
+    assert!(res.units.is_empty());
+    assert!(!res.exact);
+}
+
+#[test]
+fn it_should_mark_patterns_inexact() {
+    let base = unit(Unit {
+        pattern: vec![RcStr::from("^a")].into_iter().collect(),
+        ..typed(Type::String)
+    });
+
+    let res = run(vec![typed_unit(Type::String)], vec![base]);
+
+    assert!(res.units.is_empty());
+    assert!(!res.exact);
+}
+
+#[test]
+fn it_should_leave_out_inexpressible_meets() {
+    let strings = vec![typed_unit(Type::String)];
+
+    let derived = unit(Unit {
+        contains: Some(strings.clone()),
+        ..typed(Type::Array)
+    });
+
+    // The complement requires an item other than a string, next to `contains` of the derived.
+    let base = unit(Unit {
+        items: Some(strings),
+        ..typed(Type::Array)
+    });
+
+    let res = run(vec![derived], vec![base]);
+
+    assert!(res.units.is_empty());
+    assert!(!res.exact);
+}
+
+#[test]
+fn it_should_complement_boolean_constants() {
+    let base = unit(Unit {
+        const_: Some(true.into()),
+        ..Unit::default()
+    });
+
+    let res = run(vec![typed_unit(Type::Boolean)], vec![base]);
+
+    assert_eq!(
+        res.units,
+        vec![unit(Unit {
+            const_: Some(false.into()),
+            ..typed(Type::Boolean)
+        })]
+    );
+    assert!(res.exact);
+}
+
+#[test]
+fn it_should_limit_units() {
+    // Every unit doubles the complement.
+    let base = (0..10)
+        .map(|i| {
+            unit(Unit {
+                required: vec![
+                    RcStr::from(format!("a{}", i)),
+                    RcStr::from(format!("b{}", i)),
+                ]
+                .into_iter()
+                .collect(),
+                ..Unit::default()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let res = subtract(&[Unit::default().into()], &base, 100);
+    assert!(matches!(res, Err(Error::TooManyUnits(100))));
+}
@@ -0,0 +1,40 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+use serde_json;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The schema violates the JSON Schema specification.
+    InvalidSchema(String),
+    /// The `pattern` or a key of `patternProperties` is not a valid regular expression.
+    InvalidRegex(String),
+    /// The `$ref` doesn't point to any subschema.
+    UnresolvedRef(String),
+    /// The schema spreads into more units than the limit.
+    TooManyUnits(usize),
+    /// The schema uses a feature that cannot be checked yet.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidSchema(reason) => write!(f, "invalid schema: {}", reason),
+            Error::InvalidRegex(pattern) => write!(f, "invalid regex: {}", pattern),
+            Error::UnresolvedRef(reference) => write!(f, "unresolved $ref: {}", reference),
+            Error::TooManyUnits(limit) => {
+                write!(f, "schema spreads into more than {} units", limit)
+            }
+            Error::Unsupported(feature) => write!(f, "unsupported feature: {}", feature),
+        }
+    }
+}
+
+impl StdError for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Error {
+        Error::InvalidSchema(err.to_string())
+    }
+}
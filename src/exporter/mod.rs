@@ -0,0 +1,216 @@
+//! Serialization of units back into draft-07 JSON Schemas.
+//!
+//! The output is canonical: keywords, properties and variants are sorted,
+//! so equal units are serialized identically.
+
+use serde_json::{Map, Value};
+
+use schema::{number, Type};
+use unit::{Names, Point, Props, RcUnit, Unit};
+
+#[cfg(test)]
+mod spec;
+
+const SCHEMA: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Exports the disjunction of units as a standalone schema.
+///
+/// Without units it's `{"not": {}}` rather than `false`, so the schema can declare its draft.
+pub fn export(units: &[RcUnit]) -> Value {
+    let mut schema = match export_variants(units) {
+        Value::Bool(false) => json!({ "not": {} }),
+        schema => schema,
+    };
+
+    if let Value::Object(ref mut map) = schema {
+        map.insert("$schema".into(), SCHEMA.into());
+    }
+
+    schema
+}
+
+/// Exports the disjunction of units, which is `false` if there are no units.
+pub fn export_variants(units: &[RcUnit]) -> Value {
+    let mut variants = units.iter().map(|u| export_unit(u)).collect::<Vec<_>>();
+
+    match variants.len() {
+        0 => Value::Bool(false),
+        1 => variants.remove(0),
+        _ => {
+            variants.sort_by_cached_key(Value::to_string);
+            variants.dedup();
+            json!({ "anyOf": variants })
+        }
+    }
+}
+
+pub fn export_unit(unit: &Unit) -> Value {
+    let mut map = Map::new();
+
+    let mut put = |keyword: &str, value: Value| {
+        map.insert(keyword.into(), value);
+    };
+
+    if let Some(type_) = unit.type_ {
+        put("type", type_name(type_).into());
+    }
+
+    if let Some(ref const_) = unit.const_ {
+        put("const", Value::clone(const_));
+    }
+
+    if let Some(multiple_of) = unit.multiple_of {
+        put("multipleOf", number(multiple_of));
+    }
+
+    if let Some(maximum) = unit.maximum {
+        put(
+            limit(maximum, "maximum", "exclusiveMaximum"),
+            number(maximum.value),
+        );
+    }
+
+    if let Some(minimum) = unit.minimum {
+        put(
+            limit(minimum, "minimum", "exclusiveMinimum"),
+            number(minimum.value),
+        );
+    }
+
+    if let Some(max_length) = unit.max_length {
+        put("maxLength", max_length.into());
+    }
+
+    if let Some(min_length) = unit.min_length {
+        put("minLength", min_length.into());
+    }
+
+    if let Some(ref format) = unit.format {
+        put("format", format.as_str().into());
+    }
+
+    if !unit.tuple.is_empty() {
+        put(
+            "items",
+            unit.tuple.iter().map(|v| export_variants(v)).collect(),
+        );
+
+        if let Some(ref additional) = unit.additional_items {
+            put("additionalItems", export_variants(additional));
+        }
+    } else if let Some(ref items) = unit.items {
+        put("items", export_variants(items));
+    }
+
+    if let Some(max_items) = unit.max_items {
+        put("maxItems", max_items.into());
+    }
+
+    if let Some(min_items) = unit.min_items {
+        put("minItems", min_items.into());
+    }
+
+    if unit.unique_items {
+        put("uniqueItems", true.into());
+    }
+
+    if let Some(ref contains) = unit.contains {
+        put("contains", export_variants(contains));
+    }
+
+    if let Some(max_properties) = unit.max_properties {
+        put("maxProperties", max_properties.into());
+    }
+
+    if let Some(min_properties) = unit.min_properties {
+        put("minProperties", min_properties.into());
+    }
+
+    if !unit.required.is_empty() {
+        put("required", sorted(&unit.required).into());
+    }
+
+    if !unit.properties.is_empty() {
+        put("properties", export_props(&unit.properties));
+    }
+
+    if !unit.pattern_props.is_empty() {
+        put("patternProperties", export_props(&unit.pattern_props));
+    }
+
+    if let Some(ref additional) = unit.additional_props {
+        put("additionalProperties", export_variants(additional));
+    }
+
+    if let Some(ref names) = unit.property_names {
+        put("propertyNames", export_variants(names));
+    }
+
+    if let Some(ref default) = unit.default {
+        put("default", Value::clone(default));
+    }
+
+    if unit.read_only {
+        put("readOnly", true.into());
+    }
+
+    if unit.write_only {
+        put("writeOnly", true.into());
+    }
+
+    if unit.deprecated {
+        put("deprecated", true.into());
+    }
+
+    // A unit can have several patterns, but a schema only one per object.
+    let mut patterns = sorted(&unit.pattern).into_iter();
+
+    if let Some(pattern) = patterns.next() {
+        map.insert("pattern".into(), pattern.into());
+    }
+
+    let rest = patterns
+        .map(|pattern| json!({ "pattern": pattern }))
+        .collect::<Vec<_>>();
+
+    if !rest.is_empty() {
+        map.insert("allOf".into(), rest.into());
+    }
+
+    Value::Object(map)
+}
+
+fn export_props(props: &Props) -> Value {
+    Value::Object(
+        props
+            .iter()
+            .map(|(name, variants)| (name.to_string(), export_variants(variants)))
+            .collect(),
+    )
+}
+
+fn limit(point: Point, inclusive: &'static str, exclusive: &'static str) -> &'static str {
+    if point.inclusive {
+        inclusive
+    } else {
+        exclusive
+    }
+}
+
+fn sorted(names: &Names) -> Vec<String> {
+    let mut names = names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn type_name(type_: Type) -> &'static str {
+    match type_ {
+        Type::Object => "object",
+        Type::Array => "array",
+        Type::Boolean => "boolean",
+        Type::Integer => "integer",
+        Type::Number => "number",
+        Type::String => "string",
+        Type::Null => "null",
+    }
+}
@@ -0,0 +1,67 @@
+use super::*;
+
+use schema::RcStr;
+
+fn unit(unit: Unit) -> RcUnit {
+    unit.into()
+}
+
+#[test]
+fn it_should_export_limits() {
+    let schema = export_unit(&Unit {
+        type_: Some(Type::Number),
+        maximum: Some(Point::exc(10.)),
+        multiple_of: Some(0.5),
+        minimum: Some(Point::inc(0.)),
+        ..Unit::default()
+    });
+
+    assert_eq!(
+        schema,
+        json!({"type": "number", "exclusiveMaximum": 10, "minimum": 0, "multipleOf": 0.5})
+    );
+}
+
+#[test]
+fn it_should_sort_variants() {
+    let string = unit(Unit {
+        type_: Some(Type::String),
+        ..Unit::default()
+    });
+
+    let null = unit(Unit {
+        type_: Some(Type::Null),
+        ..Unit::default()
+    });
+
+    assert_eq!(
+        export_variants(&[string.clone(), null.clone()]),
+        export_variants(&[null, string])
+    );
+}
+
+#[test]
+fn it_should_export_empty_disjunctions_as_never() {
+    assert_eq!(export_variants(&[]), json!(false));
+    assert_eq!(
+        export(&[]),
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "not": {}})
+    );
+}
+
+#[test]
+fn it_should_split_patterns() {
+    let mut pattern = Names::new();
+    pattern.insert(RcStr::from("^b"));
+    pattern.insert(RcStr::from("^a"));
+
+    let schema = export_unit(&Unit {
+        pattern,
+        ..Unit::default()
+    });
+
+    assert_eq!(
+        schema,
+        json!({"pattern": "^a", "allOf": [{"pattern": "^b"}]})
+    );
+}
@@ -0,0 +1,194 @@
+//! Inference of schemas from sample instances.
+//!
+//! Instances are grouped by type, and every group is described by a unit with observed bounds:
+//! ranges of numbers, lengths of strings and arrays, and keys present in all objects.
+
+use serde_json::{Number, Value};
+
+use schema::{RcMixed, RcStr, Type};
+use unit::{Point, RcUnit, Unit, Variants};
+
+#[cfg(test)]
+mod spec;
+
+/// Infers units of the instances.
+///
+/// Strings and integers with at most `max_enum` distinct values are enumerated.
+pub fn infer(instances: &[Value], max_enum: usize) -> Variants {
+    Inferrer { max_enum }.variants(instances.iter().collect())
+}
+
+struct Inferrer {
+    max_enum: usize,
+}
+
+impl Inferrer {
+    fn variants(&self, values: Vec<&Value>) -> Variants {
+        let mut null = false;
+        let mut boolean = false;
+        let mut numbers = Vec::new();
+        let mut strings = Vec::new();
+        let mut arrays = Vec::new();
+        let mut objects = Vec::new();
+
+        for value in values {
+            match value {
+                Value::Null => null = true,
+                Value::Bool(_) => boolean = true,
+                Value::Number(number) => numbers.push(number),
+                Value::String(string) => strings.push(string.as_str()),
+                Value::Array(items) => arrays.push(items),
+                Value::Object(_) => objects.push(value),
+            }
+        }
+
+        let mut res = Vec::new();
+
+        if null {
+            res.push(typed(Type::Null));
+        }
+
+        if boolean {
+            res.push(typed(Type::Boolean));
+        }
+
+        if !numbers.is_empty() {
+            res.extend(self.numbers(&numbers));
+        }
+
+        if !strings.is_empty() {
+            res.extend(self.strings(&strings));
+        }
+
+        if !arrays.is_empty() {
+            res.push(self.array(&arrays));
+        }
+
+        if !objects.is_empty() {
+            res.push(self.object(&objects));
+        }
+
+        res.into_iter().map(RcUnit::from).collect()
+    }
+
+    fn numbers(&self, numbers: &[&Number]) -> Vec<Unit> {
+        let integer = numbers.iter().all(|n| n.is_i64() || n.is_u64());
+
+        if integer {
+            let values = numbers.iter().map(|&n| Value::from(n.clone()));
+
+            if let Some(units) = self.enumerate(values, Type::Integer) {
+                return units;
+            }
+        }
+
+        let values = numbers.iter().filter_map(|n| n.as_f64());
+        let min = values.clone().fold(f64::INFINITY, f64::min);
+        let max = values.fold(f64::NEG_INFINITY, f64::max);
+
+        vec![Unit {
+            minimum: Some(Point::inc(min)),
+            maximum: Some(Point::inc(max)),
+            ..typed(if integer { Type::Integer } else { Type::Number })
+        }]
+    }
+
+    fn strings(&self, strings: &[&str]) -> Vec<Unit> {
+        let values = strings.iter().map(|&s| Value::from(s));
+
+        if let Some(units) = self.enumerate(values, Type::String) {
+            return units;
+        }
+
+        let lengths = strings.iter().map(|s| s.chars().count() as u32);
+
+        vec![Unit {
+            min_length: lengths.clone().min(),
+            max_length: lengths.max(),
+            ..typed(Type::String)
+        }]
+    }
+
+    fn array(&self, arrays: &[&Vec<Value>]) -> Unit {
+        let items = arrays
+            .iter()
+            .flat_map(|items| items.iter())
+            .collect::<Vec<_>>();
+        let lengths = arrays.iter().map(|items| items.len() as u32);
+
+        Unit {
+            items: Some(items)
+                .filter(|items| !items.is_empty())
+                .map(|items| self.variants(items)),
+            min_items: lengths.clone().min(),
+            max_items: lengths.max(),
+            ..typed(Type::Array)
+        }
+    }
+
+    fn object(&self, objects: &[&Value]) -> Unit {
+        let mut keys = objects
+            .iter()
+            .filter_map(|o| o.as_object())
+            .flat_map(|props| props.keys())
+            .collect::<Vec<_>>();
+
+        keys.sort();
+        keys.dedup();
+
+        let mut unit = typed(Type::Object);
+
+        for key in keys {
+            let values = objects
+                .iter()
+                .filter_map(|o| o.get(key))
+                .collect::<Vec<_>>();
+
+            let name = RcStr::from(key.as_str());
+
+            // Keys missing from some samples are optional.
+            if values.len() == objects.len() {
+                unit.required.insert(name.clone());
+            }
+
+            unit.properties.insert(name, self.variants(values));
+        }
+
+        unit
+    }
+
+    /// Builds a unit per distinct value, unless there are more than `max_enum` of them.
+    fn enumerate<I>(&self, values: I, type_: Type) -> Option<Vec<Unit>>
+    where
+        I: Iterator<Item = Value>,
+    {
+        let mut distinct: Vec<Value> = Vec::new();
+
+        for value in values {
+            if !distinct.contains(&value) {
+                if distinct.len() == self.max_enum {
+                    return None;
+                }
+
+                distinct.push(value);
+            }
+        }
+
+        let units = distinct
+            .into_iter()
+            .map(|value| Unit {
+                const_: Some(RcMixed::from(value)),
+                ..typed(type_)
+            })
+            .collect();
+
+        Some(units)
+    }
+}
+
+fn typed(type_: Type) -> Unit {
+    Unit {
+        type_: Some(type_),
+        ..Unit::default()
+    }
+}
@@ -0,0 +1,111 @@
+use super::*;
+
+fn run(instances: Value, max_enum: usize) -> Variants {
+    match instances {
+        Value::Array(instances) => infer(&instances, max_enum),
+        _ => unreachable!(),
+    }
+}
+
+fn unit(unit: Unit) -> RcUnit {
+    unit.into()
+}
+
+#[test]
+fn it_should_infer_ranges() {
+    let res = run(json!([3, -1, 10]), 0);
+
+    assert_eq!(
+        res,
+        vec![unit(Unit {
+            minimum: Some(Point::inc(-1.)),
+            maximum: Some(Point::inc(10.)),
+            ..typed(Type::Integer)
+        })]
+    );
+
+    let res = run(json!([3, 0.5]), 0);
+    assert_eq!(res[0].type_, Some(Type::Number));
+}
+
+#[test]
+fn it_should_infer_string_lengths() {
+    let res = run(json!(["ab", "", "абв"]), 0);
+
+    assert_eq!(
+        res,
+        vec![unit(Unit {
+            min_length: Some(0),
+            max_length: Some(3),
+            ..typed(Type::String)
+        })]
+    );
+}
+
+#[test]
+fn it_should_enumerate_few_values() {
+    let res = run(json!(["on", "off", "on"]), 2);
+
+    assert_eq!(
+        res,
+        vec![
+            unit(Unit {
+                const_: Some("on".into()),
+                ..typed(Type::String)
+            }),
+            unit(Unit {
+                const_: Some("off".into()),
+                ..typed(Type::String)
+            }),
+        ]
+    );
+
+    assert_eq!(run(json!(["a", "b", "c"]), 2)[0].const_, None);
+}
+
+#[test]
+fn it_should_infer_required_keys() {
+    let res = run(json!([{"id": 1, "name": "a"}, {"id": 2}]), 0);
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(
+        res[0].required,
+        vec![RcStr::from("id")].into_iter().collect()
+    );
+    assert_eq!(res[0].properties.len(), 2);
+}
+
+#[test]
+fn it_should_infer_items() {
+    let res = run(json!([[1, null], []]), 0);
+
+    assert_eq!(res[0].min_items, Some(0));
+    assert_eq!(res[0].max_items, Some(2));
+    assert_eq!(
+        res[0].items,
+        Some(vec![
+            unit(typed(Type::Null)),
+            unit(Unit {
+                minimum: Some(Point::inc(1.)),
+                maximum: Some(Point::inc(1.)),
+                ..typed(Type::Integer)
+            }),
+        ])
+    );
+}
+
+#[test]
+fn it_should_keep_a_unit_per_type() {
+    let res = run(json!([null, "a", 1, true, null]), 0);
+
+    let types = res.iter().map(|u| u.type_).collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![
+            Some(Type::Null),
+            Some(Type::Boolean),
+            Some(Type::Integer),
+            Some(Type::String),
+        ]
+    );
+}
@@ -0,0 +1,101 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+use schema::Rc_;
+use unit::{RcUnit, Unit};
+
+#[cfg(test)]
+mod spec;
+
+/// Hash-conses units, so structurally equal ones share the same allocation.
+///
+/// Nested units are interned first, so children of interned units can be hashed by pointers.
+#[derive(Default, Clone)]
+pub struct Interner {
+    units: HashSet<Key>,
+    // Addresses are stable, because the interner keeps all units alive.
+    canonical: HashSet<usize>,
+}
+
+impl Interner {
+    pub fn intern(&mut self, unit: RcUnit) -> RcUnit {
+        if self.canonical.contains(&address(&unit)) {
+            return unit;
+        }
+
+        let mut unit = Rc_::unwrap_or_clone(unit);
+
+        // Variants are moved out, so units owned only by this one aren't cloned.
+        for variants in unit.nested_mut() {
+            *variants = mem::take(variants)
+                .into_iter()
+                .map(|variant| self.intern(variant))
+                .collect();
+        }
+
+        let key = Key(RcUnit::from(unit));
+
+        if let Some(existing) = self.units.get(&key) {
+            return existing.0.clone();
+        }
+
+        let unit = key.0.clone();
+        self.canonical.insert(address(&unit));
+        self.units.insert(key);
+
+        unit
+    }
+}
+
+fn address(unit: &RcUnit) -> usize {
+    Rc_::as_ptr(unit) as usize
+}
+
+#[derive(Clone)]
+struct Key(RcUnit);
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Key) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Key {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let unit: &Unit = &self.0;
+
+        unit.type_.hash(state);
+        unit.format.hash(state);
+        unit.max_length.hash(state);
+        unit.min_length.hash(state);
+        unit.max_items.hash(state);
+        unit.min_items.hash(state);
+        unit.max_properties.hash(state);
+        unit.min_properties.hash(state);
+        unit.unique_items.hash(state);
+        unit.maximum.map(|p| p.value.to_bits()).hash(state);
+        unit.minimum.map(|p| p.value.to_bits()).hash(state);
+        unit.multiple_of.map(f64::to_bits).hash(state);
+        unit.required.len().hash(state);
+
+        // Combine hashes of nested units in an order-independent way.
+        let nested = unit
+            .nested()
+            .map(|variants| {
+                let mut hasher = DefaultHasher::new();
+
+                for variant in variants {
+                    address(variant).hash(&mut hasher);
+                }
+
+                hasher.finish()
+            })
+            .fold(0u64, u64::wrapping_add);
+
+        nested.hash(state);
+    }
+}
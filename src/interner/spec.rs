@@ -0,0 +1,53 @@
+use super::*;
+
+use schema::{RcStr, Rc_};
+use unit::Unit;
+
+fn object(prop: &str, maximum: f64) -> Unit {
+    let item = Unit {
+        maximum: Some(::unit::Point::inc(maximum)),
+        ..Unit::default()
+    };
+
+    Unit {
+        properties: vec![(RcStr::from(prop), vec![item.into()])]
+            .into_iter()
+            .collect(),
+        ..Unit::default()
+    }
+}
+
+#[test]
+fn it_should_share_equal_units() {
+    let mut interner = Interner::default();
+
+    let a = interner.intern(object("a", 42.).into());
+    let b = interner.intern(object("a", 42.).into());
+
+    assert!(Rc_::ptr_eq(&a, &b));
+}
+
+#[test]
+fn it_should_share_equal_children() {
+    let mut interner = Interner::default();
+
+    let a = interner.intern(object("a", 42.).into());
+    let b = interner.intern(object("b", 42.).into());
+
+    assert!(!Rc_::ptr_eq(&a, &b));
+    assert!(Rc_::ptr_eq(
+        &a.properties[&RcStr::from("a")][0],
+        &b.properties[&RcStr::from("b")][0],
+    ));
+}
+
+#[test]
+fn it_should_distinguish_different_units() {
+    let mut interner = Interner::default();
+
+    let a = interner.intern(object("a", 42.).into());
+    let b = interner.intern(object("a", 43.).into());
+
+    assert!(!Rc_::ptr_eq(&a, &b));
+    assert_ne!(a, b);
+}
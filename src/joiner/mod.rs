@@ -0,0 +1,253 @@
+//! Joins of units: the tightest unit matching values of both units.
+//!
+//! It's the dual of merging. A restriction is kept only if both units have it,
+//! and then it's widened to cover both of them.
+
+use std::cmp;
+
+use patterns::Patterns;
+use schema::Type;
+use unit::{Names, Point, Props, RcUnit, Unit, Variants};
+
+#[cfg(test)]
+mod spec;
+
+pub fn join(dst: &mut Unit, src: &Unit) {
+    join_unique(&mut dst.const_, &src.const_);
+    join_type(&mut dst.type_, src.type_);
+    join_unique(&mut dst.format, &src.format);
+    join_nested(&mut dst.items, &src.items);
+    join_nested(&mut dst.additional_items, &src.additional_items);
+    join_nested(&mut dst.property_names, &src.property_names);
+    join_nested(&mut dst.contains, &src.contains);
+    join_tuple(dst, src);
+    join_properties(dst, src);
+    join_pattern_props(&mut dst.pattern_props, &src.pattern_props);
+    join_nested(&mut dst.additional_props, &src.additional_props);
+
+    join_multiple_of(&mut dst.multiple_of, &src.multiple_of);
+    join_point(&mut dst.maximum, &src.maximum, upper);
+    join_point(&mut dst.minimum, &src.minimum, lower);
+
+    join_count(&mut dst.max_length, &src.max_length, cmp::max);
+    join_count(&mut dst.min_length, &src.min_length, cmp::min);
+    join_count(&mut dst.max_items, &src.max_items, cmp::max);
+    join_count(&mut dst.min_items, &src.min_items, cmp::min);
+    join_count(&mut dst.max_properties, &src.max_properties, cmp::max);
+    join_count(&mut dst.min_properties, &src.min_properties, cmp::min);
+
+    dst.unique_items = dst.unique_items && src.unique_items;
+
+    join_unique(&mut dst.default, &src.default);
+    dst.read_only = dst.read_only && src.read_only;
+    dst.write_only = dst.write_only && src.write_only;
+    dst.deprecated = dst.deprecated && src.deprecated;
+
+    join_set(&mut dst.pattern, &src.pattern);
+    join_set(&mut dst.required, &src.required);
+}
+
+/// Joins units of the same type, so the result has a unit per type at most.
+///
+/// Joining units of different types would drop all restrictions specific to them.
+pub fn join_variants(units: &[RcUnit]) -> Variants {
+    let mut res: Vec<Unit> = Vec::new();
+
+    for unit in units {
+        match res.iter_mut().find(|r| kind(r.type_) == kind(unit.type_)) {
+            Some(r) => join(r, unit),
+            None => res.push(Unit::clone(unit)),
+        }
+    }
+
+    res.into_iter().map(RcUnit::from).collect()
+}
+
+/// `integer` is joined with `number`.
+fn kind(type_: Option<Type>) -> Option<Type> {
+    match type_ {
+        Some(Type::Integer) => Some(Type::Number),
+        t => t,
+    }
+}
+
+fn join_type(dst: &mut Option<Type>, src: Option<Type>) {
+    *dst = match (*dst, src) {
+        (Some(d), Some(s)) if d == s => Some(d),
+        (Some(d), Some(s)) if kind(Some(d)) == kind(Some(s)) => Some(Type::Number),
+        _ => None,
+    };
+}
+
+fn join_unique<T: PartialEq>(dst: &mut Option<T>, src: &Option<T>) {
+    if dst.as_ref() != src.as_ref() {
+        *dst = None;
+    }
+}
+
+/// Divisors are joined into their greatest common divisor.
+#[allow(clippy::float_cmp)]
+fn join_multiple_of(dst: &mut Option<f64>, src: &Option<f64>) {
+    *dst = match (*dst, *src) {
+        (Some(d), Some(s)) if d % s == 0. => Some(s),
+        (Some(d), Some(s)) if s % d == 0. => Some(d),
+        (Some(d), Some(s)) if d.fract() == 0. && s.fract() == 0. => Some(gcd(d, s)),
+        _ => None,
+    };
+}
+
+fn gcd(a: f64, b: f64) -> f64 {
+    if b == 0. {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn join_point<F>(dst: &mut Option<Point>, src: &Option<Point>, strategy: F)
+where
+    F: Fn(Point, Point) -> Point,
+{
+    *dst = match (*dst, *src) {
+        (Some(d), Some(s)) => Some(strategy(d, s)),
+        _ => None,
+    };
+}
+
+/// The looser of upper bounds, which is inclusive if any of them is.
+#[allow(clippy::float_cmp)]
+fn upper(a: Point, b: Point) -> Point {
+    if a.value > b.value || a.value == b.value && a.inclusive {
+        a
+    } else {
+        b
+    }
+}
+
+/// The looser of lower bounds, which is inclusive if any of them is.
+#[allow(clippy::float_cmp)]
+fn lower(a: Point, b: Point) -> Point {
+    if a.value < b.value || a.value == b.value && a.inclusive {
+        a
+    } else {
+        b
+    }
+}
+
+fn join_count<T, F>(dst: &mut Option<T>, src: &Option<T>, strategy: F)
+where
+    T: Ord + Copy,
+    F: Fn(T, T) -> T,
+{
+    *dst = match (&*dst, src) {
+        (Some(a), Some(b)) => Some(strategy(*a, *b)),
+        _ => None,
+    };
+}
+
+fn join_set(dst: &mut Names, src: &Names) {
+    dst.retain(|name| src.contains(name));
+}
+
+fn join_nested(dst: &mut Option<Variants>, src: &Option<Variants>) {
+    *dst = match (dst.take(), src) {
+        (Some(d), Some(s)) => Some(union(&d, s)),
+        _ => None,
+    };
+}
+
+/// Tuples of different lengths are dropped together with items after them.
+fn join_tuple(dst: &mut Unit, src: &Unit) {
+    if dst.tuple.len() != src.tuple.len() {
+        dst.tuple.clear();
+        dst.items = None;
+        dst.additional_items = None;
+        return;
+    }
+
+    for (d, s) in dst.tuple.iter_mut().zip(src.tuple.iter()) {
+        *d = union(d, s);
+    }
+}
+
+/// Properties declared only by one unit are constrained by additional ones of the other unit.
+fn join_properties(dst: &mut Unit, src: &Unit) {
+    let patterns = Patterns::default();
+    let mut props = Props::new();
+
+    for (prop, d) in &dst.properties {
+        let s = src
+            .properties
+            .get(prop)
+            .cloned()
+            .unwrap_or_else(|| additional(src, prop, &patterns));
+
+        props.insert(prop.clone(), union(d, &s));
+    }
+
+    for (prop, s) in src.properties.iter() {
+        if !dst.properties.contains_key(prop) {
+            let d = additional(dst, prop, &patterns);
+            props.insert(prop.clone(), union(&d, s));
+        }
+    }
+
+    // Properties matching anything are dropped, unless other keywords would apply to them.
+    let open = dst.additional_props.is_none() || src.additional_props.is_none();
+    let anything = RcUnit::from(Unit::default());
+
+    props.retain(|prop, variants| {
+        let matched = dst
+            .pattern_props
+            .keys()
+            .chain(src.pattern_props.keys())
+            .any(|p| patterns.is_match(p, prop));
+
+        !(open && !matched && variants.contains(&anything))
+    });
+
+    dst.properties = props;
+}
+
+/// Variants undeclared properties match, which is anything if patterns are involved.
+fn additional(unit: &Unit, prop: &str, patterns: &Patterns) -> Variants {
+    let matched = unit
+        .pattern_props
+        .keys()
+        .any(|p| patterns.is_match(p, prop));
+
+    match unit.additional_props {
+        Some(ref additional) if !matched => additional.clone(),
+        _ => vec![Unit::default().into()],
+    }
+}
+
+/// Patterns declared only by one unit match anything, so other keywords don't apply.
+fn join_pattern_props(dst: &mut Props, src: &Props) {
+    let anything = || vec![RcUnit::from(Unit::default())];
+
+    for (pattern, d) in dst.iter_mut() {
+        *d = match src.get(pattern) {
+            Some(s) => union(d, s),
+            None => anything(),
+        };
+    }
+
+    for pattern in src.keys() {
+        if !dst.contains_key(pattern) {
+            dst.insert(pattern.clone(), anything());
+        }
+    }
+}
+
+fn union(dst: &[RcUnit], src: &[RcUnit]) -> Variants {
+    let mut res = dst.to_vec();
+
+    for s in src {
+        if !res.contains(s) {
+            res.push(s.clone());
+        }
+    }
+
+    res
+}
@@ -0,0 +1,136 @@
+use super::*;
+
+use schema::RcStr;
+
+fn typed(type_: Type) -> RcUnit {
+    Unit {
+        type_: Some(type_),
+        ..Unit::default()
+    }
+    .into()
+}
+
+fn joined(mut dst: Unit, src: &Unit) -> Unit {
+    join(&mut dst, src);
+    dst
+}
+
+fn names(names: &[&str]) -> Names {
+    names.iter().map(|&n| RcStr::from(n)).collect()
+}
+
+#[test]
+fn it_should_widen_bounds() {
+    let a = Unit {
+        maximum: Some(Point::exc(10.)),
+        minimum: Some(Point::inc(0.)),
+        max_length: Some(5),
+        ..Unit::default()
+    };
+
+    let b = Unit {
+        maximum: Some(Point::inc(10.)),
+        minimum: Some(Point::exc(-5.)),
+        ..Unit::default()
+    };
+
+    let res = joined(a, &b);
+
+    assert_eq!(res.maximum, Some(Point::inc(10.)));
+    assert_eq!(res.minimum, Some(Point::exc(-5.)));
+    assert_eq!(res.max_length, None);
+}
+
+#[test]
+fn it_should_intersect_required() {
+    let a = Unit {
+        required: names(&["a", "b"]),
+        ..Unit::default()
+    };
+
+    let b = Unit {
+        required: names(&["b", "c"]),
+        ..Unit::default()
+    };
+
+    assert_eq!(joined(a, &b).required, names(&["b"]));
+}
+
+#[test]
+fn it_should_join_numeric_types() {
+    let res = joined(Unit::clone(&typed(Type::Integer)), &typed(Type::Number));
+    assert_eq!(res.type_, Some(Type::Number));
+
+    let res = joined(Unit::clone(&typed(Type::Integer)), &typed(Type::String));
+    assert_eq!(res.type_, None);
+}
+
+#[test]
+fn it_should_join_divisors() {
+    let multiple = |m| Unit {
+        multiple_of: Some(m),
+        ..Unit::default()
+    };
+
+    assert_eq!(joined(multiple(4.), &multiple(6.)).multiple_of, Some(2.));
+    assert_eq!(joined(multiple(0.5), &multiple(1.5)).multiple_of, Some(0.5));
+    assert_eq!(joined(multiple(0.4), &multiple(0.5)).multiple_of, None);
+}
+
+#[test]
+fn it_should_unite_properties() {
+    let a = Unit {
+        properties: vec![(RcStr::from("id"), vec![typed(Type::Integer)])]
+            .into_iter()
+            .collect(),
+        additional_props: Some(vec![typed(Type::Null)]),
+        ..Unit::default()
+    };
+
+    let b = Unit {
+        properties: vec![(RcStr::from("id"), vec![typed(Type::String)])]
+            .into_iter()
+            .collect(),
+        ..Unit::default()
+    };
+
+    let res = joined(a.clone(), &b);
+    assert_eq!(
+        res.properties[&RcStr::from("id")],
+        vec![typed(Type::Integer), typed(Type::String)]
+    );
+    assert_eq!(res.additional_props, None);
+
+    // The property of `b` is an additional one for `a`.
+    let c = Unit {
+        properties: vec![(RcStr::from("name"), vec![typed(Type::String)])]
+            .into_iter()
+            .collect(),
+        ..Unit::default()
+    };
+
+    let res = joined(a, &c);
+    assert_eq!(
+        res.properties[&RcStr::from("name")],
+        vec![typed(Type::Null), typed(Type::String)]
+    );
+
+    // Anything matches `id` in `c`, so it's unconstrained.
+    assert!(!res.properties.contains_key(&RcStr::from("id")));
+}
+
+#[test]
+fn it_should_keep_a_unit_per_type() {
+    let bounded = |max| -> RcUnit {
+        Unit {
+            type_: Some(Type::Integer),
+            maximum: Some(Point::inc(max)),
+            ..Unit::default()
+        }
+        .into()
+    };
+
+    let res = join_variants(&[bounded(1.), typed(Type::String), bounded(5.)]);
+
+    assert_eq!(res, vec![bounded(5.), typed(Type::String)]);
+}
@@ -1,42 +1,449 @@
 #![allow(unknown_lints)]
 
-extern crate either;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
-extern crate itertools;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+extern crate regex;
+#[macro_use]
 extern crate serde_json;
 
+mod api;
+mod asyncapi;
+mod avro;
+mod compatibility;
+mod difference;
+mod error;
+mod exporter;
+mod inferrer;
+mod interner;
+mod joiner;
 mod matcher;
 mod merger;
+mod mode;
+mod openapi;
+mod options;
+mod patterns;
+mod proto;
+mod pruner;
+mod sampler;
 mod schema;
 mod spreader;
+mod typescript;
 mod unit;
+mod validator;
+
+#[cfg(all(test, feature = "parallel"))]
+mod spec;
 
+pub use compatibility::Compatibility;
+pub use error::Error;
+pub use matcher::Mismatch;
+pub use mode::Mode;
+pub use options::Options;
 pub use schema::Schema;
 
+use interner::Interner;
+use matcher::Matcher;
+use serde_json::Value;
+use spreader::Stream;
+use unit::{RcUnit, Unit};
+use validator::Validator;
+
 #[derive(Debug)]
 pub enum Verdict {
     Success,
-    Failure,
+    Failure(Vec<Mismatch>),
+}
+
+/// Values valid against the derived schema, but not against the base one.
+#[derive(Debug)]
+pub struct Difference {
+    /// The schema of the difference, which matches nothing if there is none.
+    pub schema: Value,
+    /// Instances of the difference, checked against both schemas.
+    pub examples: Vec<Value>,
+    /// Whether the schema describes the whole difference.
+    ///
+    /// Restrictions like patterns have no complement, so values violating only them
+    /// are left out.
+    pub exact: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    Subtype,
+    Supertype,
+    Incomparable,
+}
+
+pub fn check(derived: Schema, base: Schema) -> Result<Verdict, Error> {
+    check_with(derived, base, &Options::default())
+}
+
+pub fn check_in(derived: Schema, base: Schema, mode: Mode) -> Result<Verdict, Error> {
+    let options = Options {
+        mode,
+        ..Options::default()
+    };
+
+    check_with(derived, base, &options)
+}
+
+pub fn check_with(derived: Schema, base: Schema, options: &Options) -> Result<Verdict, Error> {
+    #[cfg(not(feature = "parallel"))]
+    let check = check_batch;
+    #[cfg(feature = "parallel")]
+    let check = check_batch_in_parallel;
+
+    check_by(derived, base, options, check)
 }
 
-pub fn check(derived: Schema, base: Schema) -> Verdict {
-    let derived = spreader::spread(derived);
-    let base = spreader::spread(base);
+type CheckBatch = fn(&[RcUnit], &[RcUnit], &Matcher) -> Vec<Mismatch>;
+
+fn check_by(
+    derived: Schema,
+    base: Schema,
+    options: &Options,
+    check: CheckBatch,
+) -> Result<Verdict, Error> {
+    let mut interner = Interner::default();
+    let matcher = Matcher::new(options.mode);
+
+    // Base units are matched many times, but derived ones are checked only once.
+    let base = intern(&mut interner, spreader::spread(base, options.max_units)?);
+
+    // Pruning relies on transitivity, which only strict subtyping has.
+    let base = if options.mode.is_strict() {
+        pruner::prune(base, &mut interner, &matcher)
+    } else {
+        base
+    };
+
+    let derived = spreader::stream(derived, options.max_units)?;
 
     //println!("========= DERIVED ====");
     //println!("{:#?}", derived);
     //println!("=========  BASE   ====");
     //println!("{:#?}", base);
 
-    for d in &derived {
-        let is_subtype = base.iter().any(|b| matcher::subtype(d, b));
+    let mut mismatches = check_units(derived, &base, &interner, options.mode, check)?;
+
+    if mismatches.is_empty() {
+        return Ok(Verdict::Success);
+    }
+
+    mismatches.sort();
+    mismatches.dedup();
+
+    Ok(Verdict::Failure(mismatches))
+}
+
+/// Checks whether the instance is valid against the schema.
+pub fn validate(schema: Schema, instance: &Value) -> Result<bool, Error> {
+    let validator = Validator::default();
+
+    for unit in spreader::stream(schema, Options::default().max_units)? {
+        if validator.matches(&unit?, instance) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Counts units the schema is spread into, which is roughly the cost of checking it.
+///
+/// Only for benchmarks.
+#[doc(hidden)]
+pub fn count_units(schema: Schema, options: &Options) -> Result<usize, Error> {
+    let mut count = 0;
+
+    for unit in spreader::stream(schema, options.max_units)? {
+        unit?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+pub fn compare(derived: Schema, base: Schema) -> Result<Relation, Error> {
+    let max_units = Options::default().max_units;
+    let mut interner = Interner::default();
+    let matcher = Matcher::new(Mode::Strict);
+    let derived = intern(&mut interner, spreader::spread(derived, max_units)?);
+    let base = intern(&mut interner, spreader::spread(base, max_units)?);
+
+    let relation = match (
+        includes(&derived, &base, &matcher),
+        includes(&base, &derived, &matcher),
+    ) {
+        (true, true) => Relation::Equal,
+        (true, false) => Relation::Subtype,
+        (false, true) => Relation::Supertype,
+        (false, false) => Relation::Incomparable,
+    };
+
+    Ok(relation)
+}
+
+/// Checks that the derived OpenAPI document is a backward compatible version of the base one.
+///
+/// Request payloads are checked in `Mode::Request` and response ones in `Mode::Response`,
+/// so `options.mode` is ignored. Mismatches point into the derived document.
+pub fn check_openapi(derived: &Value, base: &Value, options: &Options) -> Result<Verdict, Error> {
+    openapi::check(derived, base, options.max_units)
+}
+
+/// Checks that messages of the derived AsyncAPI document are compatible with the base one.
+///
+/// Payloads and headers are checked in strict mode, so `options.mode` is ignored.
+pub fn check_asyncapi(
+    derived: &Value,
+    base: &Value,
+    compatibility: Compatibility,
+    options: &Options,
+) -> Result<Verdict, Error> {
+    asyncapi::check(derived, base, compatibility, options.max_units)
+}
+
+/// Converts an Avro schema into a schema of its JSON representation.
+pub fn from_avro(avro: &Value) -> Result<Schema, Error> {
+    avro::convert(avro)
+}
+
+/// Converts the message of the `.proto` source into a schema of its proto3 JSON encoding.
+pub fn from_proto(source: &str, message: &str) -> Result<Schema, Error> {
+    proto::convert(source, message)
+}
+
+/// Converts the declared type of the TypeScript source into a schema of its values.
+pub fn from_typescript(source: &str, name: &str) -> Result<Schema, Error> {
+    typescript::convert(source, name)
+}
+
+/// Normalizes the schema into a canonical `anyOf` of units.
+///
+/// References are inlined, `allOf`s are merged and subsumed variants are removed.
+pub fn normalize(schema: Schema, options: &Options) -> Result<Value, Error> {
+    let mut interner = Interner::default();
+    let matcher = Matcher::new(Mode::Strict);
+    let units = intern(&mut interner, spreader::spread(schema, options.max_units)?);
+    let units = pruner::prune(units, &mut interner, &matcher);
+
+    Ok(exporter::export(&units))
+}
+
+/// Builds a schema matching values valid against both schemas.
+pub fn intersect(a: Schema, b: Schema) -> Result<Value, Error> {
+    intersect_all(vec![a, b])
+}
+
+/// Builds a schema matching values valid against all the schemas.
+///
+/// Every schema is spread once, and the units are merged one schema at a time.
+pub fn intersect_all(schemas: Vec<Schema>) -> Result<Value, Error> {
+    let max_units = Options::default().max_units;
+    let mut interner = Interner::default();
+    let matcher = Matcher::new(Mode::Strict);
+    // The unconstrained unit matches everything, so it's neutral for merging.
+    let mut units = vec![interner.intern(Unit::default().into())];
 
-        if !is_subtype {
-            return Verdict::Failure;
+    for schema in schemas {
+        let other = intern(&mut interner, spreader::spread(schema, max_units)?);
+        let merged = merger::merge_variants(&units, &other)?;
+
+        if merged.len() > max_units {
+            return Err(Error::TooManyUnits(max_units));
+        }
+
+        let merged = merged.into_iter().map(|u| interner.intern(u)).collect();
+        units = pruner::prune(merged, &mut interner, &matcher);
+    }
+
+    Ok(exporter::export(&units))
+}
+
+/// Builds a schema matching values valid against any of the schemas.
+pub fn union(a: Schema, b: Schema) -> Result<Value, Error> {
+    union_all(vec![a, b])
+}
+
+/// Builds a schema matching values valid against any of the schemas.
+pub fn union_all(schemas: Vec<Schema>) -> Result<Value, Error> {
+    let max_units = Options::default().max_units;
+    let mut interner = Interner::default();
+    let matcher = Matcher::new(Mode::Strict);
+    let mut units = Vec::new();
+
+    for schema in schemas {
+        units.extend(intern(&mut interner, spreader::spread(schema, max_units)?));
+    }
+
+    let units = pruner::prune(units, &mut interner, &matcher);
+
+    Ok(exporter::export(&units))
+}
+
+/// Builds the least upper bound of the schemas: a schema with a unit per type,
+/// each one the tightest unit matching all units of that type.
+///
+/// Unlike `union`, it over-approximates, e.g. ranges are widened to cover the gaps.
+pub fn join(schemas: Vec<Schema>) -> Result<Value, Error> {
+    let max_units = Options::default().max_units;
+    let mut interner = Interner::default();
+    let matcher = Matcher::new(Mode::Strict);
+    let mut units = Vec::new();
+
+    for schema in schemas {
+        units.extend(intern(&mut interner, spreader::spread(schema, max_units)?));
+    }
+
+    let units = joiner::join_variants(&units);
+    let units = units.into_iter().map(|u| interner.intern(u)).collect();
+    let units = pruner::prune(units, &mut interner, &matcher);
+
+    Ok(exporter::export(&units))
+}
+
+/// Infers a schema of the sample instances, with a unit per type describing observed bounds.
+///
+/// Strings and integers with at most `max_enum` distinct values become enums.
+pub fn infer(instances: &[Value], max_enum: usize) -> Value {
+    exporter::export(&inferrer::infer(instances, max_enum))
+}
+
+/// Describes values the derived schema newly accepts, i.e. `derived ∧ ¬base`.
+pub fn difference(derived: Schema, base: Schema) -> Result<Difference, Error> {
+    let max_units = Options::default().max_units;
+    let mut interner = Interner::default();
+    let matcher = Matcher::new(Mode::Strict);
+    let derived = intern(&mut interner, spreader::spread(derived, max_units)?);
+    let base = intern(&mut interner, spreader::spread(base, max_units)?);
+
+    let rest = difference::subtract(&derived, &base, max_units)?;
+    let units = rest.units.into_iter().map(|u| interner.intern(u)).collect();
+    let units = pruner::prune(units, &mut interner, &matcher);
+
+    // Samples ignore some restrictions, so only real instances are kept.
+    let validator = Validator::default();
+    let mut examples = Vec::new();
+
+    for example in units.iter().filter_map(|u| sampler::sample(u)) {
+        if derived.iter().any(|d| validator.matches(d, &example))
+            && !base.iter().any(|b| validator.matches(b, &example))
+            && !examples.contains(&example)
+        {
+            examples.push(example);
+        }
+    }
+
+    examples.sort_by_cached_key(Value::to_string);
+
+    Ok(Difference {
+        schema: exporter::export(&units),
+        examples,
+        exact: rest.exact,
+    })
+}
+
+pub fn equivalent(a: Schema, b: Schema) -> Result<bool, Error> {
+    Ok(compare(a, b)? == Relation::Equal)
+}
+
+/// How many derived units are interned and matched together.
+const BATCH_SIZE: usize = 1024;
+
+/// Collects mismatches of derived units that don't match the base.
+///
+/// Derived units are taken in batches, which get their own interner and matcher,
+/// so only one batch of derived units is kept in memory.
+fn check_units(
+    mut derived: Stream,
+    base: &[RcUnit],
+    interner: &Interner,
+    mode: Mode,
+    check: CheckBatch,
+) -> Result<Vec<Mismatch>, Error> {
+    let mut mismatches = Vec::new();
+
+    loop {
+        // The copy knows base units, so derived units share their equal subtrees.
+        let mut interner = interner.clone();
+        let matcher = Matcher::new(mode);
+
+        let batch = derived
+            .by_ref()
+            .take(BATCH_SIZE)
+            .map(|d| d.map(|d| prepare(d, &mut interner, &matcher)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if batch.is_empty() {
+            break;
         }
+
+        mismatches.extend(check(&batch, base, &matcher));
+    }
+
+    Ok(mismatches)
+}
+
+#[cfg(any(test, not(feature = "parallel")))]
+fn check_batch(batch: &[RcUnit], base: &[RcUnit], matcher: &Matcher) -> Vec<Mismatch> {
+    batch
+        .iter()
+        .flat_map(|d| check_unit(d, base, matcher))
+        .collect()
+}
+
+/// Checks the batch using all cores.
+///
+/// Memo tables aren't shared, so workers build their own matchers. `map_init` creates one
+/// per split of the batch rather than per thread, so tables are rebuilt more often than that.
+#[cfg(feature = "parallel")]
+fn check_batch_in_parallel(batch: &[RcUnit], base: &[RcUnit], matcher: &Matcher) -> Vec<Mismatch> {
+    use rayon::prelude::*;
+
+    let mode = matcher.mode();
+
+    batch
+        .par_iter()
+        .map_init(
+            || Matcher::new(mode),
+            |matcher, d| check_unit(d, base, matcher),
+        )
+        .flatten()
+        .collect()
+}
+
+fn prepare(unit: Unit, interner: &mut Interner, matcher: &Matcher) -> RcUnit {
+    let unit = interner.intern(unit.into());
+
+    // Like the base, derived units are pruned only in strict mode.
+    if matcher.mode().is_strict() {
+        pruner::prune_nested(unit, interner, matcher)
+    } else {
+        unit
+    }
+}
+
+fn check_unit(derived: &RcUnit, base: &[RcUnit], matcher: &Matcher) -> Vec<Mismatch> {
+    if matcher.subtype_any(derived, base) {
+        Vec::new()
+    } else {
+        matcher.explain_variants(derived, base)
     }
+}
+
+fn includes(derived: &[RcUnit], base: &[RcUnit], matcher: &Matcher) -> bool {
+    derived.iter().all(|d| matcher.subtype_any(d, base))
+}
 
-    Verdict::Success
+fn intern(interner: &mut Interner, units: Vec<Unit>) -> Vec<RcUnit> {
+    units
+        .into_iter()
+        .map(|unit| interner.intern(unit.into()))
+        .collect()
 }
@@ -1,59 +1,498 @@
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
-use schema::{RcStr, Type};
-use unit::Unit;
+use merger;
+use mode::Mode;
+use patterns::Patterns;
+use schema::{Rc_, Type};
+use unit::{Names, Props, RcUnit, Unit, Variants};
+use validator::Validator;
 
 #[cfg(test)]
 mod spec;
+mod split;
 
-pub fn subtype(child: &Unit, parent: &Unit) -> bool {
-    check_opt(&child.const_, &parent.const_, |c, p| c == p)
-        && check_opt(&child.multiple_of, &parent.multiple_of, |c, p| c % p == 0.)
-        && check_opt(&child.maximum, &parent.maximum, |c, p| c.min(*p) == *c)
-        && check_opt(&child.minimum, &parent.minimum, |c, p| c.max(*p) == *c)
-        && check_opt(&child.max_length, &parent.max_length, PartialOrd::le)
-        && check_opt(&child.min_length, &parent.min_length, PartialOrd::ge)
-        && check_set(&child.pattern, &parent.pattern)
-        && check_opt(
+pub use self::split::vacuous;
+
+/// How many times a child unit can be split into cases to match a union.
+const MAX_SPLITS: usize = 3;
+/// How many cases can be matched before giving up on a union.
+const MAX_CASES: usize = 1000;
+
+/// A keyword of the parent unit that the child unit doesn't satisfy.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mismatch {
+    /// JSON Pointer to the (normalized) subschema containing the keyword.
+    pub pointer: String,
+    pub keyword: &'static str,
+}
+
+type Sink = Option<Vec<Mismatch>>;
+
+/// Checks subtyping of units, remembering results for pairs of shared units.
+///
+/// Units are expected to be interned, so equal subtrees are compared once.
+pub struct Matcher {
+    mode: Mode,
+    memo: RefCell<HashMap<(ById, ById), bool>>,
+    unions: RefCell<HashMap<(ById, Vec<ById>), bool>>,
+    cases: Cell<Option<usize>>,
+    /// Set when a mismatch is caused by running out of cases, so it isn't final.
+    inconclusive: Cell<bool>,
+    patterns: Patterns,
+    validator: Validator,
+}
+
+impl Matcher {
+    pub fn new(mode: Mode) -> Matcher {
+        Matcher {
+            mode,
+            memo: RefCell::new(HashMap::new()),
+            unions: RefCell::new(HashMap::new()),
+            cases: Cell::new(None),
+            inconclusive: Cell::new(false),
+            patterns: Patterns::default(),
+            validator: Validator::default(),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn subtype(&self, child: &RcUnit, parent: &RcUnit) -> bool {
+        if Rc_::ptr_eq(child, parent) {
+            return true;
+        }
+
+        let key = (ById(child.clone()), ById(parent.clone()));
+
+        if let Some(&res) = self.memo.borrow().get(&key) {
+            return res;
+        }
+
+        let (res, conclusive) =
+            self.conclude(|| self.walk(child, parent, &Location::Root, &mut None));
+
+        if conclusive {
+            self.memo.borrow_mut().insert(key, res);
+        }
+
+        res
+    }
+
+    /// Checks whether the child is a subtype of the union of parent units.
+    ///
+    /// If no single parent unit contains the child, the child is split into cases,
+    /// which are matched separately. The search gives up after `MAX_CASES` cases.
+    pub fn subtype_any(&self, child: &RcUnit, parent: &[RcUnit]) -> bool {
+        if parent.iter().any(|p| self.subtype(child, p)) {
+            return true;
+        }
+
+        let key = (
+            ById(child.clone()),
+            parent.iter().cloned().map(ById).collect(),
+        );
+
+        if let Some(&res) = self.unions.borrow().get(&key) {
+            return res;
+        }
+
+        // Nested unions share the budget of the outermost one.
+        let outermost = self.cases.get().is_none();
+
+        if outermost {
+            self.cases.set(Some(MAX_CASES));
+        }
+
+        let (res, conclusive) = self.conclude(|| self.covered(child, parent, MAX_SPLITS));
+
+        if conclusive {
+            self.unions.borrow_mut().insert(key, res);
+        }
+
+        if outermost {
+            self.cases.set(None);
+        }
+
+        res
+    }
+
+    /// Runs the check, also telling whether its result is final and can be remembered.
+    ///
+    /// A mismatch after running out of cases isn't, and neither are ones depending on it.
+    fn conclude<F: FnOnce() -> bool>(&self, check: F) -> (bool, bool) {
+        let outer = self.inconclusive.replace(false);
+        let res = check();
+        let inconclusive = self.inconclusive.get();
+        self.inconclusive.set(outer || inconclusive);
+
+        (res, res || !inconclusive)
+    }
+
+    /// Explains why the child doesn't match any of the parent variants.
+    ///
+    /// All mismatches with the closest variant are collected instead of stopping at the first one.
+    pub fn explain_variants(&self, child: &RcUnit, parent: &[RcUnit]) -> Vec<Mismatch> {
+        let mut out = Some(Vec::new());
+        self.walk_variants(child, parent, &Location::Root, &mut out);
+        out.unwrap_or_else(Vec::new)
+    }
+
+    fn walk(&self, child: &Unit, parent: &Unit, loc: &Location, out: &mut Sink) -> bool {
+        let mut ok = true;
+
+        // Without a sink there is no need to continue after the first mismatch.
+        macro_rules! ensure {
+            ($keyword:expr, $check:expr) => {
+                if !$check {
+                    ok = false;
+
+                    match out {
+                        Some(out) => out.push(Mismatch::new(loc, $keyword)),
+                        None => return false,
+                    }
+                }
+            };
+        }
+
+        macro_rules! descend {
+            ($check:expr) => {
+                if !$check {
+                    ok = false;
+
+                    if out.is_none() {
+                        return false;
+                    }
+                }
+            };
+        }
+
+        // A constant matches if its value is valid, e.g. `1` matches `maximum: 10`.
+        // Other modes only relax the parent, so keywords are compared below if it isn't.
+        if let Some(ref value) = child.const_ {
+            if check_opt(&child.format, &parent.format, |c, p| c == p)
+                && self.validator.matches(parent, value)
+            {
+                return true;
+            }
+        }
+
+        ensure!(
+            "const",
+            check_opt(&child.const_, &parent.const_, |c, p| c == p)
+        );
+        ensure!(
+            "multipleOf",
+            check_opt(&child.multiple_of, &parent.multiple_of, |c, p| c % p == 0.)
+        );
+        ensure!(
+            "maximum",
+            check_opt(&child.maximum, &parent.maximum, |c, p| c.min(*p) == *c)
+        );
+        ensure!(
+            "minimum",
+            check_opt(&child.minimum, &parent.minimum, |c, p| c.max(*p) == *c)
+        );
+        ensure!(
+            "maxLength",
+            check_opt(&child.max_length, &parent.max_length, PartialOrd::le)
+        );
+        ensure!(
+            "minLength",
+            check_opt(&child.min_length, &parent.min_length, PartialOrd::ge)
+        );
+        ensure!("pattern", check_set(&child.pattern, &parent.pattern));
+        descend!(self.walk_nested(
             &child.additional_items,
             &parent.additional_items,
-            subtype_box,
-        ) && check_opt(&child.items, &parent.items, subtype_box)
-        && check_tuple(&child.tuple, &parent.tuple)
-        && check_opt(&child.max_items, &parent.max_items, PartialOrd::le)
-        && check_opt(&child.min_items, &parent.min_items, PartialOrd::ge)
-        && (child.unique_items || !parent.unique_items)
-        && check_opt(
-            &child.max_properties,
-            &parent.max_properties,
-            PartialOrd::le,
-        )
-        && check_opt(
-            &child.min_properties,
-            &parent.min_properties,
-            PartialOrd::ge,
-        )
-        && check_set(&child.required, &parent.required)
+            loc,
+            "additionalItems",
+            out
+        ));
+        descend!(self.walk_nested(&child.items, &parent.items, loc, "items", out));
+        descend!(self.walk_tuple(&child.tuple, &parent.tuple, loc, out));
+        ensure!(
+            "maxItems",
+            check_opt(&child.max_items, &parent.max_items, PartialOrd::le)
+        );
+        ensure!(
+            "minItems",
+            check_opt(&child.min_items, &parent.min_items, PartialOrd::ge)
+        );
+        ensure!("uniqueItems", child.unique_items || !parent.unique_items);
+        ensure!(
+            "maxProperties",
+            check_opt(
+                &child.max_properties,
+                &parent.max_properties,
+                PartialOrd::le,
+            )
+        );
+        ensure!(
+            "minProperties",
+            check_opt(
+                &child.min_properties,
+                &parent.min_properties,
+                PartialOrd::ge,
+            )
+        );
+        ensure!("required", check_required(child, parent, self.mode));
         // TODO: we should check properties and additional_props together.
-        && check_opt(
+        descend!(self.walk_nested(
             &child.additional_props,
             &parent.additional_props,
-            subtype_box,
-        ) && check_opt(&child.property_names, &parent.property_names, subtype_box)
-        && check_opt(&child.contains, &parent.contains, subtype_box)
-        && check_opt(&child.format, &parent.format, |c, p| c == p)
-        && check_props(&child.properties, &parent.properties)
-        && check_props(&child.pattern_props, &parent.pattern_props)
-        && check_opt(&child.type_, &parent.type_, |c, p| match (c, p) {
-            (Type::Integer, Type::Number) => true,
-            (a, b) => a == b,
+            loc,
+            "additionalProperties",
+            out
+        ));
+        descend!(self.walk_nested(
+            &child.property_names,
+            &parent.property_names,
+            loc,
+            "propertyNames",
+            out
+        ));
+        descend!(self.walk_nested(&child.contains, &parent.contains, loc, "contains", out));
+        ensure!(
+            "format",
+            check_opt(&child.format, &parent.format, |c, p| c == p)
+        );
+        descend!(self.walk_props(
+            &child.properties,
+            &parent.properties,
+            &loc.join("properties"),
+            out
+        ));
+        descend!(self.walk_extra_props(child, parent, loc, out));
+        descend!(self.walk_props(
+            &child.pattern_props,
+            &parent.pattern_props,
+            &loc.join("patternProperties"),
+            out
+        ));
+        ensure!(
+            "type",
+            check_opt(&child.type_, &parent.type_, |c, p| match (c, p) {
+                (Type::Integer, Type::Number) => true,
+                (a, b) => a == b,
+            })
+        );
+
+        ok
+    }
+
+    fn walk_nested(
+        &self,
+        child: &Option<Variants>,
+        parent: &Option<Variants>,
+        loc: &Location,
+        keyword: &'static str,
+        out: &mut Sink,
+    ) -> bool {
+        match (child, parent) {
+            (Some(c), Some(p)) => self.walk_union(c, p, &loc.join(keyword), out),
+            (None, Some(_)) => fail(out, loc, keyword),
+            _ => true,
+        }
+    }
+
+    /// Every child variant must be a subtype of some parent variant.
+    fn walk_union(
+        &self,
+        child: &[RcUnit],
+        parent: &[RcUnit],
+        loc: &Location,
+        out: &mut Sink,
+    ) -> bool {
+        let mut ok = true;
+
+        for c in child {
+            if !self.walk_variants(c, parent, loc, out) {
+                ok = false;
+
+                if out.is_none() {
+                    break;
+                }
+            }
+        }
+
+        ok
+    }
+
+    fn covered(&self, child: &Unit, parent: &[RcUnit], depth: usize) -> bool {
+        if depth == 0 {
+            return false;
+        }
+
+        split::splits(child, parent).into_iter().any(|cases| {
+            cases.iter().all(|case| {
+                // Running out of cases is inconclusive, so it's treated as a mismatch.
+                match self.cases.get() {
+                    Some(0) => {
+                        self.inconclusive.set(true);
+                        return false;
+                    }
+                    left => self.cases.set(left.map(|n| n - 1)),
+                }
+
+                let mut piece = child.clone();
+
+                match merger::merge(&mut piece, case) {
+                    Ok(true) => {
+                        split::vacuous(&piece)
+                            || parent
+                                .iter()
+                                .any(|p| self.walk(&piece, p, &Location::Root, &mut None))
+                            || self.covered(&piece, parent, depth - 1)
+                    }
+                    Ok(false) => true,
+                    Err(_) => false,
+                }
+            })
         })
+    }
+
+    fn walk_variants(
+        &self,
+        child: &RcUnit,
+        parent: &[RcUnit],
+        loc: &Location,
+        out: &mut Sink,
+    ) -> bool {
+        if self.subtype_any(child, parent) {
+            return true;
+        }
+
+        let out = match out {
+            Some(out) => out,
+            None => return false,
+        };
+
+        let closest = parent
+            .iter()
+            .map(|p| {
+                let mut mismatches = Some(Vec::new());
+                self.walk(child, p, loc, &mut mismatches);
+                mismatches.unwrap_or_else(Vec::new)
+            })
+            .min_by_key(Vec::len);
+
+        match closest {
+            Some(mismatches) => out.extend(mismatches),
+            // No variants at all, so the parent is equivalent to `false`.
+            None => out.push(Mismatch::new(loc, "false")),
+        }
+
+        false
+    }
+
+    fn walk_tuple(
+        &self,
+        child: &[Variants],
+        parent: &[Variants],
+        loc: &Location,
+        out: &mut Sink,
+    ) -> bool {
+        if child.len() != parent.len() {
+            return fail(out, loc, "items");
+        }
+
+        let loc = loc.join("items");
+        let mut ok = true;
+
+        for (i, (c, p)) in child.iter().zip(parent).enumerate() {
+            let index = i.to_string();
+
+            if !self.walk_union(c, p, &loc.join(&index), out) {
+                ok = false;
+
+                if out.is_none() {
+                    break;
+                }
+            }
+        }
+
+        ok
+    }
+
+    /// Properties declared only by the child are restricted by patterns
+    /// or `additionalProperties` of the parent.
+    fn walk_extra_props(
+        &self,
+        child: &Unit,
+        parent: &Unit,
+        loc: &Location,
+        out: &mut Sink,
+    ) -> bool {
+        let mut ok = true;
+
+        for (prop, c) in &child.properties {
+            if parent.properties.contains_key(prop) || is_omitted(c, self.mode) {
+                continue;
+            }
+
+            let mut valid = true;
+            let mut matched = false;
+            let patterns_loc = loc.join("patternProperties");
+
+            for (pattern, p) in &parent.pattern_props {
+                if self.patterns.is_match(pattern, prop) {
+                    matched = true;
+                    valid &= self.walk_union(c, p, &patterns_loc.join(pattern), out);
+                }
+            }
+
+            if let (false, Some(p)) = (matched, &parent.additional_props) {
+                valid &= self.walk_union(c, p, &loc.join("additionalProperties"), out);
+            }
+
+            if !valid {
+                ok = false;
+
+                if out.is_none() {
+                    break;
+                }
+            }
+        }
+
+        ok
+    }
+
+    fn walk_props(&self, child: &Props, parent: &Props, loc: &Location, out: &mut Sink) -> bool {
+        let mut ok = true;
+
+        for (prop, p) in parent {
+            let valid = match child.get(prop) {
+                Some(c) => {
+                    is_omitted(c, self.mode)
+                        || is_omitted(p, self.mode)
+                        || self.walk_union(c, p, &loc.join(prop), out)
+                }
+                None => is_omitted(p, self.mode) || fail(out, &loc.join(prop), keyword_of(loc)),
+            };
+
+            if !valid {
+                ok = false;
+
+                if out.is_none() {
+                    break;
+                }
+            }
+        }
+
+        ok
+    }
 }
 
-#[allow(borrowed_box)]
-fn subtype_box(child: &Box<Unit>, parent: &Box<Unit>) -> bool {
-    subtype(&*child, &*parent)
+fn fail(out: &mut Sink, loc: &Location, keyword: &'static str) -> bool {
+    if let Some(out) = out {
+        out.push(Mismatch::new(loc, keyword));
+    }
+
+    false
 }
 
 fn check_opt<T>(child: &Option<T>, parent: &Option<T>, chk: impl Fn(&T, &T) -> bool) -> bool {
@@ -64,31 +503,91 @@ fn check_opt<T>(child: &Option<T>, parent: &Option<T>, chk: impl Fn(&T, &T) -> b
     }
 }
 
-fn check_set<T: Eq + Hash>(child: &HashSet<T>, parent: &HashSet<T>) -> bool {
-    child.intersection(&parent).count() == parent.len()
+fn check_set(child: &Names, parent: &Names) -> bool {
+    parent.iter().all(|item| child.contains(item))
+}
+
+fn check_required(child: &Unit, parent: &Unit, mode: Mode) -> bool {
+    if mode.is_strict() {
+        return check_set(&child.required, &parent.required);
+    }
+
+    parent
+        .required
+        .iter()
+        .filter(|prop| !child.required.contains(prop))
+        .all(|prop| {
+            let c = child.properties.get(prop);
+            let p = parent.properties.get(prop);
+
+            // Readers fill in defaults and shouldn't rely on deprecated properties.
+            c.is_some_and(|c| is_omitted(c, mode))
+                || p.is_some_and(|p| {
+                    is_omitted(p, mode)
+                        || !p.is_empty() && p.iter().all(|p| p.default.is_some() || p.deprecated)
+                })
+        })
+}
+
+/// Whether the property is never written in the current direction.
+fn is_omitted(prop: &[RcUnit], mode: Mode) -> bool {
+    match mode {
+        Mode::Strict => false,
+        Mode::Request => !prop.is_empty() && prop.iter().all(|p| p.read_only),
+        Mode::Response => !prop.is_empty() && prop.iter().all(|p| p.write_only),
+    }
+}
+
+fn keyword_of(loc: &Location) -> &'static str {
+    match loc {
+        Location::Nested(_, "patternProperties") => "patternProperties",
+        _ => "properties",
+    }
 }
 
-fn check_tuple(child: &[Unit], parent: &[Unit]) -> bool {
-    if child.len() != parent.len() {
-        return false;
+/// Compares units by identity: cheap and exact for interned units.
+struct ById(RcUnit);
+
+impl PartialEq for ById {
+    fn eq(&self, other: &ById) -> bool {
+        Rc_::ptr_eq(&self.0, &other.0)
     }
+}
 
-    child
-        .into_iter()
-        .zip(parent.into_iter())
-        .all(|(s, p)| subtype(s, p))
+impl Eq for ById {}
+
+impl Hash for ById {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc_::as_ptr(&self.0).hash(state);
+    }
 }
 
-fn check_props(child: &HashMap<RcStr, Unit>, parent: &HashMap<RcStr, Unit>) -> bool {
-    for (prop, p) in parent {
-        if let Some(c) = child.get(prop) {
-            if !subtype(c, p) {
-                return false;
+enum Location<'a> {
+    Root,
+    Nested(&'a Location<'a>, &'a str),
+}
+
+impl<'a> Location<'a> {
+    fn join(&'a self, segment: &'a str) -> Location<'a> {
+        Location::Nested(self, segment)
+    }
+
+    fn to_pointer(&self) -> String {
+        match self {
+            Location::Root => String::new(),
+            Location::Nested(parent, segment) => {
+                let escaped = segment.replace('~', "~0").replace('/', "~1");
+                format!("{}/{}", parent.to_pointer(), escaped)
             }
-        } else {
-            return false;
         }
     }
+}
 
-    true
+impl Mismatch {
+    fn new(loc: &Location, keyword: &'static str) -> Mismatch {
+        Mismatch {
+            pointer: loc.to_pointer(),
+            keyword,
+        }
+    }
 }
@@ -1,7 +1,20 @@
 use super::*;
 
+fn subtype(child: &Unit, parent: &Unit) -> bool {
+    subtype_in(child, parent, Mode::Strict)
+}
+
+fn subtype_in(child: &Unit, parent: &Unit, mode: Mode) -> bool {
+    Matcher::new(mode).subtype(&child.clone().into(), &parent.clone().into())
+}
+
+fn explain_variants(child: &Unit, parent: &[Unit], mode: Mode) -> Vec<Mismatch> {
+    let parent = parent.iter().cloned().map(RcUnit::from).collect::<Vec<_>>();
+    Matcher::new(mode).explain_variants(&child.clone().into(), &parent)
+}
+
 mod subtype {
-    use std::collections::HashMap;
+    use unit::Props;
 
     use schema::{RcMixed, RcStr, Type};
     use unit::{Point, Unit};
@@ -45,20 +58,20 @@ mod subtype {
 
     macro_rules! test_nested {
         ($field:ident) => {
-            let a = Box::new(Unit {
+            let a = vec![Unit {
                 required: vec![RcStr::from("a"), RcStr::from("b")].into_iter().collect(),
                 ..Unit::default()
-            });
+            }.into()];
 
-            let b = Box::new(Unit {
+            let b = vec![Unit {
                 required: vec![RcStr::from("a")].into_iter().collect(),
                 ..Unit::default()
-            });
+            }.into()];
 
-            let c = Box::new(Unit {
+            let c = vec![Unit {
                 required: vec![RcStr::from("c")].into_iter().collect(),
                 ..Unit::default()
-            });
+            }.into()];
 
             test!([$field] None, Some(a.clone()) => false);
             test!([$field] Some(a.clone()), None => true);
@@ -74,27 +87,27 @@ mod subtype {
                 ..Unit::default()
             };
 
-            let mut ha = HashMap::new();
-            ha.insert(RcStr::from("a"), a);
+            let mut ha = Props::new();
+            ha.insert(RcStr::from("a"), vec![a.into()]);
 
             let b = Unit {
                 required: vec![RcStr::from("a")].into_iter().collect(),
                 ..Unit::default()
             };
 
-            let mut hb = HashMap::new();
-            hb.insert(RcStr::from("a"), b);
+            let mut hb = Props::new();
+            hb.insert(RcStr::from("a"), vec![b.into()]);
 
             let c = Unit {
                 required: vec![RcStr::from("c")].into_iter().collect(),
                 ..Unit::default()
             };
 
-            let mut hc = HashMap::new();
-            hc.insert(RcStr::from("a"), c);
+            let mut hc = Props::new();
+            hc.insert(RcStr::from("a"), vec![c.into()]);
 
-            test!([$field] HashMap::new(), ha.clone() => false);
-            test!([$field] ha.clone(), HashMap::new() => true);
+            test!([$field] Props::new(), ha.clone() => false);
+            test!([$field] ha.clone(), Props::new() => true);
             test!([$field] ha.clone(), hc => false);
             test!([$field] ha.clone(), hb => true);
         };
@@ -107,6 +120,26 @@ mod subtype {
         test!([const_] Some(RcMixed::from(42)), Some(RcMixed::from(42)) => true);
     }
 
+    #[test]
+    fn it_should_check_const_against_other_keywords() {
+        use super::subtype;
+
+        let child = |value: i32| Unit {
+            const_: Some(RcMixed::from(value)),
+            type_: Some(Type::Integer),
+            ..Unit::default()
+        };
+
+        let parent = Unit {
+            type_: Some(Type::Number),
+            maximum: Some(Point::inc(10.)),
+            ..Unit::default()
+        };
+
+        assert!(subtype(&child(10), &parent));
+        assert!(!subtype(&child(11), &parent));
+    }
+
     #[test]
     fn it_should_check_multiple_of() {
         test!([multiple_of] None, Some(42.) => false);
@@ -264,8 +297,418 @@ mod subtype {
             ..Unit::default()
         };
 
-        test!([tuple] vec![a.clone()], vec![a.clone(), b.clone()] => false);
-        test!([tuple] vec![a.clone()], vec![b.clone()] => true);
-        test!([tuple] vec![a.clone()], vec![c.clone()] => false);
+        test!([tuple] vec![vec![a.clone().into()]], vec![vec![a.clone().into()], vec![b.clone().into()]] => false);
+        test!([tuple] vec![vec![a.clone().into()]], vec![vec![b.clone().into()]] => true);
+        test!([tuple] vec![vec![a.clone().into()]], vec![vec![c.clone().into()]] => false);
+    }
+}
+
+mod subtype_in {
+    use unit::Props;
+
+    use super::subtype_in;
+    use mode::Mode;
+    use schema::{RcMixed, RcStr};
+    use unit::Unit;
+
+    fn object(props: Vec<(&str, Unit)>, required: Vec<&str>) -> Unit {
+        Unit {
+            properties: props
+                .into_iter()
+                .map(|(k, v)| (RcStr::from(k), vec![v.into()]))
+                .collect::<Props>(),
+            required: required.into_iter().map(RcStr::from).collect(),
+            ..Unit::default()
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_read_only_props_in_requests() {
+        let id = Unit {
+            read_only: true,
+            ..Unit::default()
+        };
+
+        let child = object(vec![], vec![]);
+        let parent = object(vec![("id", id)], vec!["id"]);
+
+        assert!(!subtype_in(&child, &parent, Mode::Strict));
+        assert!(subtype_in(&child, &parent, Mode::Request));
+        assert!(!subtype_in(&child, &parent, Mode::Response));
+    }
+
+    #[test]
+    fn it_should_ignore_write_only_props_in_responses() {
+        let password = Unit {
+            write_only: true,
+            ..Unit::default()
+        };
+
+        let child = object(vec![], vec![]);
+        let parent = object(vec![("password", password)], vec!["password"]);
+
+        assert!(!subtype_in(&child, &parent, Mode::Request));
+        assert!(subtype_in(&child, &parent, Mode::Response));
+    }
+
+    #[test]
+    fn it_should_allow_missing_props_with_defaults() {
+        let limit = Unit {
+            default: Some(RcMixed::from(10)),
+            ..Unit::default()
+        };
+
+        let child = object(vec![("limit", limit.clone())], vec![]);
+        let parent = object(vec![("limit", limit)], vec!["limit"]);
+
+        assert!(!subtype_in(&child, &parent, Mode::Strict));
+        assert!(subtype_in(&child, &parent, Mode::Request));
+        assert!(subtype_in(&child, &parent, Mode::Response));
+    }
+
+    #[test]
+    fn it_should_allow_missing_deprecated_props() {
+        let legacy = Unit {
+            deprecated: true,
+            ..Unit::default()
+        };
+
+        let child = object(vec![("legacy", Unit::default())], vec![]);
+        let parent = object(vec![("legacy", legacy)], vec!["legacy"]);
+
+        assert!(!subtype_in(&child, &parent, Mode::Strict));
+        assert!(subtype_in(&child, &parent, Mode::Response));
+    }
+
+    // Constants valid in strict mode are valid in other modes, which only relax the parent.
+    #[test]
+    fn it_should_check_const_in_all_modes() {
+        let child = Unit {
+            const_: Some(RcMixed::from(json!({"name": "a"}))),
+            ..Unit::default()
+        };
+
+        let name = Unit {
+            max_length: Some(5),
+            ..Unit::default()
+        };
+
+        let parent = object(vec![("name", name)], vec!["name"]);
+
+        assert!(subtype_in(&child, &parent, Mode::Strict));
+        assert!(subtype_in(&child, &parent, Mode::Request));
+        assert!(subtype_in(&child, &parent, Mode::Response));
+    }
+
+    #[test]
+    fn it_should_fall_back_to_keywords_for_const_with_omitted_props() {
+        let child = Unit {
+            const_: Some(RcMixed::from(json!({"name": "a"}))),
+            ..Unit::default()
+        };
+
+        let read_only = Unit {
+            read_only: true,
+            ..Unit::default()
+        };
+
+        let write_only = Unit {
+            write_only: true,
+            ..Unit::default()
+        };
+
+        // The value lacks `id`, which only servers send.
+        let parent = object(vec![("id", read_only)], vec!["id"]);
+
+        assert!(!subtype_in(&child, &parent, Mode::Strict));
+        assert!(subtype_in(&child, &parent, Mode::Request));
+        assert!(!subtype_in(&child, &parent, Mode::Response));
+
+        // The value lacks `password`, which only clients send.
+        let parent = object(vec![("password", write_only)], vec!["password"]);
+
+        assert!(!subtype_in(&child, &parent, Mode::Strict));
+        assert!(!subtype_in(&child, &parent, Mode::Request));
+        assert!(subtype_in(&child, &parent, Mode::Response));
+    }
+}
+
+mod explain_variants {
+    use super::{explain_variants, Mismatch};
+    use mode::Mode;
+    use schema::{RcStr, Type};
+    use unit::{Point, Unit};
+
+    fn mismatch(pointer: &str, keyword: &'static str) -> Mismatch {
+        Mismatch {
+            pointer: pointer.to_owned(),
+            keyword,
+        }
+    }
+
+    #[test]
+    fn it_should_be_empty_for_subtypes() {
+        let child = Unit {
+            type_: Some(Type::Integer),
+            ..Unit::default()
+        };
+
+        let parent = Unit {
+            type_: Some(Type::Number),
+            ..Unit::default()
+        };
+
+        assert_eq!(explain_variants(&child, &[parent], Mode::Strict), vec![]);
+    }
+
+    #[test]
+    fn it_should_collect_all_mismatches() {
+        let child = Unit {
+            type_: Some(Type::String),
+            max_items: Some(10),
+            ..Unit::default()
+        };
+
+        let parent = Unit {
+            type_: Some(Type::Number),
+            max_items: Some(5),
+            min_items: Some(1),
+            ..Unit::default()
+        };
+
+        let mut res = explain_variants(&child, &[parent], Mode::Strict);
+        res.sort();
+
+        assert_eq!(
+            res,
+            vec![
+                mismatch("", "maxItems"),
+                mismatch("", "minItems"),
+                mismatch("", "type"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_point_to_nested_units() {
+        let child = Unit {
+            properties: vec![(
+                RcStr::from("a/b"),
+                vec![Unit {
+                    items: Some(vec![Unit {
+                        maximum: Some(Point::inc(42.)),
+                        ..Unit::default()
+                    }
+                    .into()]),
+                    ..Unit::default()
+                }
+                .into()],
+            )]
+            .into_iter()
+            .collect(),
+            ..Unit::default()
+        };
+
+        let parent = Unit {
+            properties: vec![
+                (
+                    RcStr::from("a/b"),
+                    vec![Unit {
+                        items: Some(vec![Unit {
+                            maximum: Some(Point::inc(10.)),
+                            ..Unit::default()
+                        }
+                        .into()]),
+                        ..Unit::default()
+                    }
+                    .into()],
+                ),
+                (RcStr::from("c"), vec![Unit::default().into()]),
+            ]
+            .into_iter()
+            .collect(),
+            ..Unit::default()
+        };
+
+        let mut res = explain_variants(&child, &[parent], Mode::Strict);
+        res.sort();
+
+        assert_eq!(
+            res,
+            vec![
+                mismatch("/properties/a~1b/items", "maximum"),
+                mismatch("/properties/c", "properties"),
+            ]
+        );
+    }
+}
+
+mod memo {
+    use super::{Matcher, RcUnit};
+    use mode::Mode;
+    use unit::{Point, Unit};
+
+    #[test]
+    fn it_should_remember_results() {
+        let matcher = Matcher::new(Mode::Strict);
+
+        let child: RcUnit = Unit {
+            maximum: Some(Point::inc(10.)),
+            ..Unit::default()
+        }
+        .into();
+
+        let parent: RcUnit = Unit {
+            maximum: Some(Point::inc(42.)),
+            ..Unit::default()
+        }
+        .into();
+
+        assert!(matcher.subtype(&child, &parent));
+        assert!(!matcher.subtype(&parent, &child));
+        assert_eq!(matcher.memo.borrow().len(), 2);
+
+        assert!(matcher.subtype(&child, &parent));
+        assert_eq!(matcher.memo.borrow().len(), 2);
+    }
+
+    #[test]
+    fn it_should_skip_identical_units() {
+        let matcher = Matcher::new(Mode::Strict);
+        let unit: RcUnit = Unit::default().into();
+
+        assert!(matcher.subtype(&unit, &unit.clone()));
+        assert!(matcher.memo.borrow().is_empty());
+    }
+}
+
+mod subtype_any {
+    use super::{ById, Matcher, RcUnit};
+    use mode::Mode;
+    use schema::{RcStr, Type};
+    use unit::{Point, Unit, Variants};
+
+    fn range(minimum: Option<Point>, maximum: Option<Point>) -> RcUnit {
+        Unit {
+            minimum,
+            maximum,
+            ..Unit::default()
+        }
+        .into()
+    }
+
+    #[test]
+    fn it_should_split_on_bounds() {
+        let matcher = Matcher::new(Mode::Strict);
+        let child = range(Some(Point::inc(0.)), Some(Point::inc(10.)));
+        let parent = vec![
+            range(None, Some(Point::exc(5.))),
+            range(Some(Point::inc(5.)), None),
+        ];
+
+        assert!(matcher.subtype_any(&child, &parent));
+    }
+
+    #[test]
+    fn it_should_respect_exclusive_bounds() {
+        let matcher = Matcher::new(Mode::Strict);
+        let child = range(Some(Point::inc(0.)), Some(Point::inc(10.)));
+        let parent = vec![
+            range(None, Some(Point::exc(5.))),
+            range(Some(Point::exc(5.)), None),
+        ];
+
+        assert!(!matcher.subtype_any(&child, &parent));
+    }
+
+    #[test]
+    fn it_should_remember_unions() {
+        let matcher = Matcher::new(Mode::Strict);
+        let child = range(Some(Point::inc(0.)), Some(Point::inc(10.)));
+        let parent = vec![
+            range(None, Some(Point::exc(5.))),
+            range(Some(Point::inc(5.)), None),
+        ];
+
+        assert!(matcher.subtype_any(&child, &parent));
+        assert!(matcher.subtype_any(&child, &parent));
+        assert_eq!(matcher.unions.borrow().len(), 1);
+    }
+
+    #[test]
+    fn it_should_give_up_on_too_many_cases() {
+        let matcher = Matcher::new(Mode::Strict);
+        let child: RcUnit = Unit::default().into();
+
+        // Every required property splits the child, but no case matches.
+        let parent = (0..40)
+            .map(|i| {
+                Unit {
+                    required: vec![RcStr::from(format!("p{}", i))].into_iter().collect(),
+                    maximum: Some(Point::inc(0.)),
+                    ..Unit::default()
+                }
+                .into()
+            })
+            .collect::<Vec<RcUnit>>();
+
+        assert!(!matcher.subtype_any(&child, &parent));
+        assert_eq!(matcher.cases.get(), None);
+        // Running out of cases isn't a final answer.
+        assert!(matcher.unions.borrow().is_empty());
+    }
+
+    fn key(child: &RcUnit, parent: &RcUnit) -> (ById, ById) {
+        (ById(child.clone()), ById(parent.clone()))
+    }
+
+    #[test]
+    fn it_should_not_remember_units_given_up_on() {
+        let matcher = Matcher::new(Mode::Strict);
+
+        let parents = (0..40)
+            .map(|i| {
+                Unit {
+                    required: vec![RcStr::from(format!("p{}", i))].into_iter().collect(),
+                    maximum: Some(Point::inc(0.)),
+                    ..Unit::default()
+                }
+                .into()
+            })
+            .collect::<Vec<RcUnit>>();
+
+        let nest = |variants: Variants| -> RcUnit {
+            Unit {
+                items: Some(variants),
+                ..Unit::default()
+            }
+            .into()
+        };
+
+        // The union of items is given up on, which decides the containing units.
+        let child = nest(vec![Unit::default().into()]);
+        let parent = nest(parents);
+
+        assert!(!matcher.subtype(&child, &parent));
+        assert!(!matcher.memo.borrow().contains_key(&key(&child, &parent)));
+        assert!(matcher.unions.borrow().is_empty());
+
+        // Final mismatches are still remembered.
+        let typed = |type_| -> RcUnit {
+            Unit {
+                type_: Some(type_),
+                ..Unit::default()
+            }
+            .into()
+        };
+
+        let string = nest(vec![typed(Type::String)]);
+        let null = nest(vec![typed(Type::Null)]);
+
+        assert!(!matcher.subtype(&string, &null));
+        assert_eq!(
+            matcher.memo.borrow().get(&key(&string, &null)),
+            Some(&false)
+        );
     }
 }
@@ -0,0 +1,125 @@
+use std::iter;
+
+use schema::{RcStr, Type};
+use unit::{Point, RcUnit, Unit};
+
+/// Ways to split the child into cases, each one narrowing it towards some parent unit.
+///
+/// Cases of a split cover the child together, so the child is a subtype of the union
+/// if every case is.
+pub fn splits(child: &Unit, parent: &[RcUnit]) -> Vec<Vec<Unit>> {
+    let mut res = Vec::new();
+
+    for p in parent {
+        if let Some(max) = p.maximum {
+            if straddles(child, max, complement(max)) {
+                push(
+                    &mut res,
+                    vec![bound(Some(max), None), bound(None, Some(complement(max)))],
+                );
+            }
+        }
+
+        if let Some(min) = p.minimum {
+            if straddles(child, complement(min), min) {
+                push(
+                    &mut res,
+                    vec![bound(None, Some(min)), bound(Some(complement(min)), None)],
+                );
+            }
+        }
+
+        if child.type_.is_none() && p.type_.is_some() {
+            push(&mut res, TYPES.iter().map(|&t| typed(t)).collect());
+        }
+
+        for prop in p.required.iter().filter(|p| cuts(child, p)) {
+            push(&mut res, vec![required(prop), absent(prop)]);
+        }
+    }
+
+    res
+}
+
+/// Checks whether the case is empty, although merging hasn't detected it.
+pub fn vacuous(unit: &Unit) -> bool {
+    let numeric = matches!(unit.type_, Some(Type::Integer) | Some(Type::Number));
+
+    match (unit.minimum, unit.maximum) {
+        (Some(min), Some(max)) => numeric && !overlaps(min, max),
+        _ => false,
+    }
+}
+
+// `integer` is omitted, because `number` covers it.
+const TYPES: [Type; 6] = [
+    Type::Null,
+    Type::Boolean,
+    Type::Object,
+    Type::Array,
+    Type::Number,
+    Type::String,
+];
+
+/// Whether the child range contains values on both sides of the cut.
+fn straddles(child: &Unit, below: Point, above: Point) -> bool {
+    let has_below = child.minimum.is_none_or(|min| overlaps(min, below));
+    let has_above = child.maximum.is_none_or(|max| overlaps(above, max));
+
+    has_below && has_above
+}
+
+/// Whether the child allows both presence and absence of the property.
+fn cuts(child: &Unit, prop: &RcStr) -> bool {
+    let forbidden = child.properties.get(prop).is_some_and(Vec::is_empty);
+
+    !child.required.contains(prop) && !forbidden
+}
+
+/// Whether some number lies between the lower and the upper bounds.
+#[allow(clippy::float_cmp)]
+fn overlaps(lower: Point, upper: Point) -> bool {
+    lower.value < upper.value || lower.value == upper.value && lower.inclusive && upper.inclusive
+}
+
+fn complement(point: Point) -> Point {
+    Point {
+        value: point.value,
+        inclusive: !point.inclusive,
+    }
+}
+
+fn push(res: &mut Vec<Vec<Unit>>, split: Vec<Unit>) {
+    if !res.contains(&split) {
+        res.push(split);
+    }
+}
+
+fn bound(maximum: Option<Point>, minimum: Option<Point>) -> Unit {
+    Unit {
+        maximum,
+        minimum,
+        ..Unit::default()
+    }
+}
+
+fn typed(type_: Type) -> Unit {
+    Unit {
+        type_: Some(type_),
+        ..Unit::default()
+    }
+}
+
+fn required(prop: &RcStr) -> Unit {
+    Unit {
+        required: iter::once(prop.clone()).collect(),
+        ..Unit::default()
+    }
+}
+
+fn absent(prop: &RcStr) -> Unit {
+    Unit {
+        properties: iter::once((prop.clone(), Vec::new())).collect(),
+        ..Unit::default()
+    }
+}
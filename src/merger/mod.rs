@@ -1,32 +1,32 @@
 use std::cmp;
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
 
-use schema::{RcStr, Type};
-use unit::{Point, Unit};
+use error::Error;
+use patterns::Patterns;
+use schema::Type;
+use unit::{Names, Point, Props, RcUnit, Unit, Variants};
 
 #[cfg(test)]
 mod spec;
 
-#[must_use]
-pub fn merge(dst: &mut Unit, src: &Unit) -> bool {
+/// Intersects the units, returning false if the result is empty.
+///
+/// Fails if the result cannot be expressed by a unit.
+pub fn merge(dst: &mut Unit, src: &Unit) -> Result<bool, Error> {
     if !(merge_unique(&mut dst.const_, &src.const_)
         && merge_type(&mut dst.type_, src.type_)
         && merge_unique(&mut dst.format, &src.format)
-        && merge_nested(&mut dst.items, &src.items)
-        && merge_nested(&mut dst.additional_items, &src.additional_items)
-        && merge_nested(&mut dst.additional_props, &src.additional_props)
-        && merge_nested(&mut dst.property_names, &src.property_names)
-        && merge_nested(&mut dst.contains, &src.contains)
-        && merge_tuple(&mut dst.tuple, &src.tuple)
-        && merge_props(&mut dst.properties, &src.properties)
-        && merge_props(&mut dst.pattern_props, &src.pattern_props))
+        && merge_tuple(&mut dst.tuple, &src.tuple)?)
     {
-        return false;
+        return Ok(false);
     }
 
-    // TODO: multiple_of
+    merge_nested(&mut dst.items, &src.items)?;
+    merge_nested(&mut dst.additional_items, &src.additional_items)?;
+    merge_nested(&mut dst.property_names, &src.property_names)?;
+    merge_contains(&mut dst.contains, &src.contains)?;
+    merge_properties(dst, src)?;
 
+    merge_multiple_of(&mut dst.multiple_of, &src.multiple_of);
     merge_point(&mut dst.maximum, &src.maximum, Point::min);
     merge_point(&mut dst.minimum, &src.minimum, Point::max);
 
@@ -39,10 +39,74 @@ pub fn merge(dst: &mut Unit, src: &Unit) -> bool {
 
     dst.unique_items = dst.unique_items || src.unique_items;
 
+    // Annotations never make units incompatible.
+    if dst.default.is_none() {
+        dst.default = src.default.clone();
+    }
+
+    dst.read_only = dst.read_only || src.read_only;
+    dst.write_only = dst.write_only || src.write_only;
+    dst.deprecated = dst.deprecated || src.deprecated;
+
     merge_set(&mut dst.pattern, &src.pattern);
     merge_set(&mut dst.required, &src.required);
 
-    true
+    Ok(satisfiable(dst))
+}
+
+/// Checks restrictions that cannot be met because of nested units without variants.
+///
+/// They only apply to arrays or objects, so units of other types are kept.
+pub fn satisfiable(unit: &Unit) -> bool {
+    let array = unit.contains.as_ref().is_none_or(|c| !c.is_empty());
+    let object = unit
+        .required
+        .iter()
+        .all(|prop| unit.properties.get(prop).is_none_or(|p| !p.is_empty()));
+
+    match unit.type_ {
+        Some(Type::Array) => array,
+        Some(Type::Object) => object,
+        _ => true,
+    }
+}
+
+/// Intersects alternatives, keeping only satisfiable combinations.
+pub fn merge_variants(dst: &[RcUnit], src: &[RcUnit]) -> Result<Variants, Error> {
+    let mut res = Vec::new();
+
+    for d in dst {
+        for s in src {
+            let mut unit = Unit::clone(d);
+
+            if merge(&mut unit, s)? {
+                res.push(unit.into());
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// Merges divisors into their least common multiple.
+#[allow(clippy::float_cmp)]
+fn merge_multiple_of(dst: &mut Option<f64>, src: &Option<f64>) {
+    *dst = match (*dst, *src) {
+        (Some(d), Some(s)) if d % s == 0. => Some(d),
+        (Some(d), Some(s)) if s % d == 0. => Some(s),
+        (Some(d), Some(s)) if d.fract() == 0. && s.fract() == 0. => Some(d / gcd(d, s) * s),
+        // TODO: the least common multiple of fractions.
+        (Some(d), Some(_)) => Some(d),
+        (x, y) => x.or(y),
+    };
+}
+
+fn gcd(a: f64, b: f64) -> f64 {
+    if b == 0. {
+        a
+    } else {
+        gcd(b, a % b)
+    }
 }
 
 fn merge_point<F>(dst: &mut Option<Point>, src: &Option<Point>, strategy: F)
@@ -66,46 +130,54 @@ where
     };
 }
 
-fn merge_nested(dst: &mut Option<Box<Unit>>, src: &Option<Box<Unit>>) -> bool {
+fn merge_nested(dst: &mut Option<Variants>, src: &Option<Variants>) -> Result<(), Error> {
     if let Some(s) = src {
         if let Some(d) = dst {
-            if !merge(d, s) {
-                return false;
-            }
+            *d = merge_variants(d, s)?;
         } else {
             *dst = src.clone();
         }
     }
 
-    true
+    Ok(())
 }
 
-fn merge_tuple(dst: &mut Vec<Unit>, src: &[Unit]) -> bool {
+/// Both items must be present, but they may differ, so `contains` isn't intersected.
+fn merge_contains(dst: &mut Option<Variants>, src: &Option<Variants>) -> Result<(), Error> {
+    match (&*dst, src) {
+        (Some(d), Some(s)) if d != s => Err(Error::Unsupported("contains in allOf")),
+        (None, Some(_)) => {
+            *dst = src.clone();
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn merge_tuple(dst: &mut Vec<Variants>, src: &[Variants]) -> Result<bool, Error> {
     if dst.is_empty() {
         dst.extend(src.iter().cloned());
-        return true;
+        return Ok(true);
     }
 
     if src.is_empty() {
-        return true;
+        return Ok(true);
     }
 
     // TODO: should we allow it if we get additional_items?
     if dst.len() != src.len() {
-        return false;
+        return Ok(false);
     }
 
     for (d, s) in dst.iter_mut().zip(src.iter()) {
-        if !merge(d, s) {
-            return false;
-        }
+        *d = merge_variants(d, s)?;
     }
 
-    true
+    Ok(true)
 }
 
-fn merge_set<T: Eq + Hash + Clone>(dst: &mut HashSet<T>, src: &HashSet<T>) {
-    dst.extend(src.into_iter().cloned());
+fn merge_set(dst: &mut Names, src: &Names) {
+    dst.extend(src.iter().cloned());
 }
 
 fn merge_type(dst: &mut Option<Type>, src: Option<Type>) -> bool {
@@ -139,18 +211,84 @@ fn merge_unique<T: PartialEq + Clone>(dst: &mut Option<T>, src: &Option<T>) -> b
     }
 }
 
-fn merge_props(dst: &mut HashMap<RcStr, Unit>, src: &HashMap<RcStr, Unit>) -> bool {
+/// Properties declared only by one unit are additional ones for the other unit,
+/// and so are properties matching only patterns of one unit.
+fn merge_properties(dst: &mut Unit, src: &Unit) -> Result<(), Error> {
+    let patterns = Patterns::default();
+    let mut src_props = src.properties.clone();
+    let mut src_patterns = src.pattern_props.clone();
+
+    // Both units are compared with the original other one, so `dst` is updated last.
+    constrain_additional(&mut src_props, dst, &patterns)?;
+    constrain_patterns(&mut src_patterns, dst, &patterns)?;
+    constrain_additional(&mut dst.properties, src, &patterns)?;
+    constrain_patterns(&mut dst.pattern_props, src, &patterns)?;
+
+    merge_props(&mut dst.properties, &src_props)?;
+    merge_props(&mut dst.pattern_props, &src_patterns)?;
+    merge_nested(&mut dst.additional_props, &src.additional_props)
+}
+
+fn constrain_additional(props: &mut Props, other: &Unit, patterns: &Patterns) -> Result<(), Error> {
+    let additional = match &other.additional_props {
+        Some(additional) => additional,
+        None => return Ok(()),
+    };
+
+    for (prop, variants) in props.iter_mut() {
+        let matched = other
+            .pattern_props
+            .keys()
+            .any(|p| patterns.is_match(p, prop));
+
+        if !other.properties.contains_key(prop) && !matched {
+            *variants = merge_variants(variants, additional)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Restricts patterns unknown to the other unit by its `additionalProperties`.
+///
+/// Properties of the other unit matching such a pattern aren't additional ones,
+/// but the pattern would restrict them too, so the intersection isn't expressible.
+/// The same goes for patterns of the other unit, which may overlap.
+fn constrain_patterns(props: &mut Props, other: &Unit, patterns: &Patterns) -> Result<(), Error> {
+    let additional = match &other.additional_props {
+        Some(additional) => additional,
+        None => return Ok(()),
+    };
+
+    for (pattern, variants) in props.iter_mut() {
+        if other.pattern_props.contains_key(pattern) {
+            continue;
+        }
+
+        let declared = other
+            .properties
+            .keys()
+            .any(|p| patterns.is_match(pattern, p));
+
+        if declared || !other.pattern_props.is_empty() {
+            return Err(Error::Unsupported("patternProperties in allOf"));
+        }
+
+        *variants = merge_variants(variants, additional)?;
+    }
+
+    Ok(())
+}
+
+fn merge_props(dst: &mut Props, src: &Props) -> Result<(), Error> {
     for (prop, s) in src {
         if let Some(d) = dst.get_mut(prop) {
-            if !merge(d, s) {
-                return false;
-            }
-
+            *d = merge_variants(d, s)?;
             continue;
         }
 
         dst.insert(prop.clone(), s.clone());
     }
 
-    true
+    Ok(())
 }
@@ -36,7 +36,8 @@ macro_rules! test {
                 $field: $src,
                 ..Unit::default()
             },
-        );
+        )
+        .unwrap();
 
         (dst, ok)
     }};
@@ -75,14 +76,14 @@ macro_rules! make_min_tests {
 }
 
 macro_rules! make_nested_tests {
-    ($field:ident) => {
+    ($field:ident => $($conflict:tt)+) => {
         mod $field {
             use unit::Unit;
 
             #[test]
             fn it_should_merge_if_unfilled() {
                 let (a, b) = (Unit::default(), Unit::default());
-                test!([$field] None, Some(Box::new(a)) => Some(Box::new(b)));
+                test!([$field] None, Some(vec![a.into()]) => Some(vec![b.into()]));
             }
 
             #[test]
@@ -103,11 +104,33 @@ macro_rules! make_nested_tests {
                     ..Unit::default()
                 };
 
-                test!([$field] Some(Box::new(a)), Some(Box::new(b)) => Some(Box::new(r)));
+                test!([$field] Some(vec![a.into()]), Some(vec![b.into()]) => Some(vec![r.into()]));
+            }
+
+            #[test]
+            fn it_should_keep_compatible_variants() {
+                use schema::Type;
+
+                let a = Unit {
+                    type_: Some(Type::Integer),
+                    ..Unit::default()
+                };
+
+                let b = Unit {
+                    type_: Some(Type::String),
+                    ..Unit::default()
+                };
+
+                let c = Unit {
+                    type_: Some(Type::Number),
+                    ..Unit::default()
+                };
+
+                test!([$field] Some(vec![a.clone().into(), b.into()]), Some(vec![c.into()]) => Some(vec![a.into()]));
             }
 
             #[test]
-            fn it_should_fail_if_cannot_merge() {
+            fn it_should_handle_conflicts() {
                 use schema::Type;
 
                 let a = Unit {
@@ -120,7 +143,7 @@ macro_rules! make_nested_tests {
                     ..Unit::default()
                 };
 
-                test!([$field] Some(Box::new(a)), Some(Box::new(b)) => FAILED);
+                test!([$field] Some(vec![a.into()]), Some(vec![b.into()]) => $($conflict)+);
             }
         }
     };
@@ -129,59 +152,62 @@ macro_rules! make_nested_tests {
 macro_rules! make_props_tests {
     ($field:ident) => {
         mod $field {
-            use std::collections::HashMap;
+            use unit::Props;
 
             use unit::Unit;
             use schema::RcStr;
 
             #[test]
             fn it_should_merge_if_unfilled() {
-                let mut a = HashMap::new();
-                a.insert(RcStr::from("foo"), Unit::default());
-                test!([$field] HashMap::new(), a.clone() => a);
+                let mut a = Props::new();
+                a.insert(RcStr::from("foo"), vec![Unit::default().into()]);
+                test!([$field] Props::new(), a.clone() => a);
             }
 
             #[test]
             fn it_should_merge_appropriate_props() {
-                let mut ha = HashMap::new();
-                ha.insert(RcStr::from("foo"), Unit {
+                let mut ha = Props::new();
+                ha.insert(RcStr::from("foo"), vec![Unit {
                     max_items: Some(42),
                     ..Unit::default()
-                });
+                }.into()]);
 
-                let mut hb = HashMap::new();
-                hb.insert(RcStr::from("foo"), Unit {
+                let mut hb = Props::new();
+                hb.insert(RcStr::from("foo"), vec![Unit {
                     min_items: Some(32),
                     ..Unit::default()
-                });
+                }.into()]);
 
-                let mut hr = HashMap::new();
-                hr.insert(RcStr::from("foo"), Unit {
+                let mut hr = Props::new();
+                hr.insert(RcStr::from("foo"), vec![Unit {
                     max_items: Some(42),
                     min_items: Some(32),
                     ..Unit::default()
-                });
+                }.into()]);
 
                 test!([$field] ha, hb => hr);
             }
 
             #[test]
-            fn it_should_fail_if_cannot_merge() {
+            fn it_should_forbid_if_cannot_merge() {
                 use schema::Type;
 
-                let mut ha = HashMap::new();
-                ha.insert(RcStr::from("foo"), Unit {
+                let mut ha = Props::new();
+                ha.insert(RcStr::from("foo"), vec![Unit {
                     type_: Some(Type::Array),
                     ..Unit::default()
-                });
+                }.into()]);
 
-                let mut hb = HashMap::new();
-                hb.insert(RcStr::from("foo"), Unit {
+                let mut hb = Props::new();
+                hb.insert(RcStr::from("foo"), vec![Unit {
                     type_: Some(Type::Integer),
                     ..Unit::default()
-                });
+                }.into()]);
 
-                test!([$field] ha, hb => FAILED);
+                let mut hr = Props::new();
+                hr.insert(RcStr::from("foo"), vec![]);
+
+                test!([$field] ha, hb => hr);
             }
         }
     };
@@ -190,26 +216,26 @@ macro_rules! make_props_tests {
 macro_rules! make_set_tests {
     ($field:ident) => {
         mod $field {
-            use std::collections::HashSet;
+            use unit::Names;
 
             use schema::RcStr;
 
             #[test]
             fn it_should_merge_if_unfilled() {
-                let mut a = HashSet::new();
+                let mut a = Names::new();
                 a.insert(RcStr::from("foo"));
-                test!([$field] HashSet::new(), a.clone() => a);
+                test!([$field] Names::new(), a.clone() => a);
             }
 
             #[test]
             fn it_should_merge_appropriate_props() {
-                let mut ha = HashSet::new();
+                let mut ha = Names::new();
                 ha.insert(RcStr::from("bar"));
 
-                let mut hb = HashSet::new();
+                let mut hb = Names::new();
                 hb.insert(RcStr::from("foo"));
 
-                let mut hr = HashSet::new();
+                let mut hr = Names::new();
                 hr.insert(RcStr::from("foo"));
                 hr.insert(RcStr::from("bar"));
 
@@ -219,11 +245,48 @@ macro_rules! make_set_tests {
     };
 }
 
+#[test]
+fn it_should_fail_if_required_prop_is_forbidden() {
+    use schema::{RcStr, Type};
+
+    let mut dst = Unit {
+        type_: Some(Type::Object),
+        required: vec![RcStr::from("foo")].into_iter().collect(),
+        properties: vec![(
+            RcStr::from("foo"),
+            vec![Unit {
+                type_: Some(Type::String),
+                ..Unit::default()
+            }
+            .into()],
+        )]
+        .into_iter()
+        .collect(),
+        ..Unit::default()
+    };
+
+    let src = Unit {
+        properties: vec![(
+            RcStr::from("foo"),
+            vec![Unit {
+                type_: Some(Type::Integer),
+                ..Unit::default()
+            }
+            .into()],
+        )]
+        .into_iter()
+        .collect(),
+        ..Unit::default()
+    };
+
+    assert!(!merge(&mut dst, &src).unwrap());
+}
+
 #[test]
 fn it_should_merge_if_nones() {
     let mut dst = Unit::default();
 
-    assert!(merge(&mut dst, &Unit::default()));
+    assert!(merge(&mut dst, &Unit::default()).unwrap());
     assert_eq!(dst, Unit::default());
 }
 
@@ -234,11 +297,10 @@ make_min_tests!(min_items);
 make_max_tests!(max_properties);
 make_min_tests!(min_properties);
 
-make_nested_tests!(items);
-make_nested_tests!(additional_items);
-make_nested_tests!(additional_props);
-make_nested_tests!(property_names);
-make_nested_tests!(contains);
+make_nested_tests!(items => Some(vec![]));
+make_nested_tests!(additional_items => Some(vec![]));
+make_nested_tests!(additional_props => Some(vec![]));
+make_nested_tests!(property_names => Some(vec![]));
 
 make_props_tests!(properties);
 make_props_tests!(pattern_props);
@@ -310,6 +372,23 @@ mod format {
     }
 }
 
+mod multiple_of {
+    #[test]
+    fn it_should_merge_if_unfilled() {
+        test!([multiple_of] None, Some(3.) => Some(3.));
+    }
+
+    #[test]
+    fn it_should_select_multiple() {
+        test!([multiple_of] Some(0.5), Some(1.5) => Some(1.5));
+    }
+
+    #[test]
+    fn it_should_select_least_common_multiple() {
+        test!([multiple_of] Some(4.), Some(6.) => Some(12.));
+    }
+}
+
 mod maximum {
     use unit::Point;
 
@@ -375,12 +454,13 @@ mod tuple {
 
     #[test]
     fn it_should_merge_if_unfilled() {
-        test!([tuple] vec![], vec![Unit::default()] => vec![Unit::default()]);
+        test!([tuple] vec![], vec![vec![Unit::default().into()]] => vec![vec![Unit::default().into()]]);
     }
 
     #[test]
     fn it_should_fail_if_different_shape() {
-        test!([tuple] vec![Unit::default(), Unit::default()], vec![Unit::default()] => FAILED);
+        let u = vec![Unit::default().into()];
+        test!([tuple] vec![u.clone(), u.clone()], vec![u] => FAILED);
     }
 
     #[test]
@@ -401,11 +481,13 @@ mod tuple {
             ..Unit::default()
         };
 
-        test!([tuple] vec![a.clone(), a.clone()], vec![Unit::default(), b] => vec![a, r]);
+        test!([tuple]
+              vec![vec![a.clone().into()], vec![a.clone().into()]],
+              vec![vec![Unit::default().into()], vec![b.into()]] => vec![vec![a.into()], vec![r.into()]]);
     }
 
     #[test]
-    fn it_should_fail_if_cannot_merge_items() {
+    fn it_should_forbid_items_if_cannot_merge() {
         use schema::Type;
 
         let a = Unit {
@@ -418,6 +500,145 @@ mod tuple {
             ..Unit::default()
         };
 
-        test!([tuple] vec![a.clone(), a.clone()], vec![Unit::default(), b] => FAILED);
+        test!([tuple]
+              vec![vec![a.clone().into()], vec![a.clone().into()]],
+              vec![vec![Unit::default().into()], vec![b.into()]] => vec![vec![a.into()], vec![]]);
+    }
+}
+
+mod pattern_and_additional_props {
+    use super::merge;
+    use error::Error;
+    use schema::{RcStr, Type};
+    use unit::{Props, RcUnit, Unit};
+
+    fn string() -> RcUnit {
+        Unit {
+            type_: Some(Type::String),
+            ..Unit::default()
+        }
+        .into()
+    }
+
+    fn props(key: &str, variants: Vec<RcUnit>) -> Props {
+        vec![(RcStr::from(key), variants)].into_iter().collect()
+    }
+
+    #[test]
+    fn it_should_restrict_patterns_of_other_unit() {
+        let mut dst = Unit {
+            pattern_props: props("^x", vec![Unit::default().into()]),
+            ..Unit::default()
+        };
+
+        let src = Unit {
+            additional_props: Some(vec![string()]),
+            ..Unit::default()
+        };
+
+        assert!(merge(&mut dst, &src).unwrap());
+        assert_eq!(dst.pattern_props, props("^x", vec![string()]));
+        assert_eq!(dst.additional_props, Some(vec![string()]));
+    }
+
+    #[test]
+    fn it_should_not_restrict_declared_props_of_other_unit() {
+        let mut dst = Unit {
+            additional_props: Some(vec![string()]),
+            ..Unit::default()
+        };
+
+        let src = Unit {
+            properties: props("a", vec![Unit::default().into()]),
+            additional_props: Some(vec![]),
+            ..Unit::default()
+        };
+
+        assert!(merge(&mut dst, &src).unwrap());
+        assert_eq!(dst.properties, props("a", vec![string()]));
+        assert_eq!(dst.additional_props, Some(vec![]));
+    }
+
+    #[test]
+    fn it_should_fail_if_patterns_may_overlap() {
+        let mut dst = Unit {
+            pattern_props: props("^x", vec![Unit::default().into()]),
+            ..Unit::default()
+        };
+
+        let src = Unit {
+            pattern_props: props("^xy", vec![Unit::default().into()]),
+            additional_props: Some(vec![string()]),
+            ..Unit::default()
+        };
+
+        match merge(&mut dst, &src) {
+            Err(Error::Unsupported(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        // The declared property matches the pattern, but isn't an additional one.
+        let src = Unit {
+            properties: props("x1", vec![Unit::default().into()]),
+            additional_props: Some(vec![string()]),
+            ..Unit::default()
+        };
+
+        match merge(&mut dst, &src) {
+            Err(Error::Unsupported(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
+
+mod contains {
+    use super::merge;
+    use error::Error;
+    use schema::Type;
+    use unit::{RcUnit, Unit};
+
+    fn typed(type_: Type) -> RcUnit {
+        Unit {
+            type_: Some(type_),
+            ..Unit::default()
+        }
+        .into()
+    }
+
+    #[test]
+    fn it_should_merge_if_unfilled() {
+        test!([contains] None, Some(vec![typed(Type::Integer)]) => Some(vec![typed(Type::Integer)]));
+    }
+
+    #[test]
+    fn it_should_fail_if_different() {
+        // An array may contain both an integer and a string.
+        let mut dst = Unit {
+            contains: Some(vec![typed(Type::Integer)]),
+            ..Unit::default()
+        };
+
+        let src = Unit {
+            contains: Some(vec![typed(Type::String)]),
+            ..Unit::default()
+        };
+
+        match merge(&mut dst, &src) {
+            Err(Error::Unsupported(_)) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn it_should_keep_non_arrays_if_nothing_matches() {
+        let mut dst = Unit {
+            contains: Some(vec![]),
+            ..Unit::default()
+        };
+
+        assert!(merge(&mut dst, &Unit::default()).unwrap());
+
+        dst.type_ = Some(Type::Array);
+        assert!(!merge(&mut dst, &Unit::default()).unwrap());
     }
 }
@@ -0,0 +1,32 @@
+use std::str::FromStr;
+
+/// Defines how annotations (`readOnly`, `writeOnly`, `default` and `deprecated`) affect checking.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Pure JSON Schema semantics, annotations are ignored.
+    #[default]
+    Strict,
+    /// Payloads written by clients: `readOnly` properties are never sent.
+    Request,
+    /// Payloads written by servers: `writeOnly` properties are never sent.
+    Response,
+}
+
+impl Mode {
+    pub(crate) fn is_strict(self) -> bool {
+        self == Mode::Strict
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Mode, String> {
+        match s {
+            "strict" => Ok(Mode::Strict),
+            "request" => Ok(Mode::Request),
+            "response" => Ok(Mode::Response),
+            _ => Err(format!("unknown mode: {}", s)),
+        }
+    }
+}
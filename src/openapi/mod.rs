@@ -0,0 +1,373 @@
+//! Compatibility checking of OpenAPI 3.0 and 3.1 documents.
+//!
+//! Operations are paired by path and method. Payloads sent by clients must be accepted
+//! by the derived document whenever the base one accepts them, and payloads sent by servers
+//! must be accepted by the base document.
+
+use serde_json::Value;
+
+use api::{self, Dialect, Document};
+use error::Error;
+use matcher::Mismatch;
+use mode::Mode;
+use options::Options;
+use Verdict;
+
+#[cfg(test)]
+mod spec;
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Checks that the derived document is a backward compatible version of the base one.
+pub fn check(derived: &Value, base: &Value, max_units: usize) -> Result<Verdict, Error> {
+    let derived = open(derived)?;
+    let base = open(base)?;
+
+    let mut checker = Checker {
+        derived: &derived,
+        base: &base,
+        max_units,
+        mismatches: Vec::new(),
+    };
+
+    checker.check_paths()?;
+
+    Ok(api::verdict(checker.mismatches))
+}
+
+fn open(root: &Value) -> Result<Document<'_>, Error> {
+    let dialect = match root.get("openapi").and_then(Value::as_str) {
+        Some(v) if v.starts_with("3.0") => Dialect::OpenApi30,
+        Some(v) if v.starts_with("3.1") => Dialect::JsonSchema,
+        Some(_) => return Err(Error::Unsupported("OpenAPI version")),
+        None if root.get("swagger").is_some() => return Err(Error::Unsupported("Swagger 2.0")),
+        None => return Err(Error::InvalidSchema("`openapi` version is required".into())),
+    };
+
+    Ok(Document::new(root, dialect))
+}
+
+struct Checker<'a> {
+    derived: &'a Document<'a>,
+    base: &'a Document<'a>,
+    max_units: usize,
+    mismatches: Vec<Mismatch>,
+}
+
+impl<'a> Checker<'a> {
+    fn check_paths(&mut self) -> Result<(), Error> {
+        let derived_paths = api::entries(self.derived.root.get("paths"));
+
+        for (path, base_item) in api::entries(self.base.root.get("paths")) {
+            let base_item = self.base.follow(base_item)?;
+            let loc = api::pointer(&["paths", path]);
+
+            // Paths are paired regardless of names of template parameters.
+            let derived_item = derived_paths
+                .iter()
+                .find(|(p, _)| template(p) == template(path));
+
+            let (derived_path, derived_item) = match derived_item {
+                Some(&(p, item)) => (p, self.derived.follow(item)?),
+                None => {
+                    self.mismatch(loc, "path");
+                    continue;
+                }
+            };
+
+            for method in METHODS {
+                let base_op = match base_item.get(*method) {
+                    Some(op) => self.base.follow(op)?,
+                    None => continue,
+                };
+
+                let loc = api::pointer(&["paths", derived_path, method]);
+
+                let derived_op = match derived_item.get(*method) {
+                    Some(op) => self.derived.follow(op)?,
+                    None => {
+                        self.mismatch(loc, "operation");
+                        continue;
+                    }
+                };
+
+                let base_op = Operation::new(base_item, base_op);
+                let derived_op = Operation::new(derived_item, derived_op);
+                self.check_operation(&derived_op, &base_op, &loc)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_operation(
+        &mut self,
+        derived: &Operation<'a>,
+        base: &Operation<'a>,
+        loc: &str,
+    ) -> Result<(), Error> {
+        self.check_parameters(derived, base, loc)?;
+
+        if let Some(body) = derived.op.get("requestBody") {
+            let loc = format!("{}/requestBody", loc);
+            self.check_request_body(self.derived.follow(body)?, base, &loc)?;
+        }
+
+        if let Some(responses) = derived.op.get("responses").and_then(Value::as_object) {
+            for (status, response) in responses {
+                let loc = format!("{}/responses/{}", loc, api::escape(status));
+                self.check_response(status, self.derived.follow(response)?, base, &loc)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_parameters(
+        &mut self,
+        derived: &Operation<'a>,
+        base: &Operation<'a>,
+        loc: &str,
+    ) -> Result<(), Error> {
+        // Pointers to base parameters aren't used.
+        let base_params = base.parameters(self.base, "")?;
+
+        for (loc, param) in derived.parameters(self.derived, loc)? {
+            let base_param = base_params
+                .iter()
+                .map(|(_, p)| *p)
+                .find(|p| same_parameter(p, param));
+
+            let base_param = match base_param {
+                Some(base_param) => base_param,
+                None => {
+                    // New optional parameters are never sent by old clients.
+                    if is_required(param) {
+                        self.mismatch(loc.to_string(), "required");
+                    }
+
+                    continue;
+                }
+            };
+
+            if is_required(param) && !is_required(base_param) {
+                self.mismatch(loc.to_string(), "required");
+            }
+
+            if let (Some(derived_schema), Some(base_schema)) =
+                (parameter_schema(param), parameter_schema(base_param))
+            {
+                let loc = format!("{}/schema", loc);
+                self.check_schema(base_schema, derived_schema, Mode::Request, &loc)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_request_body(
+        &mut self,
+        derived: &'a Value,
+        base: &Operation<'a>,
+        loc: &str,
+    ) -> Result<(), Error> {
+        let base = match base.op.get("requestBody") {
+            Some(body) => self.base.follow(body)?,
+            None => {
+                if is_required(derived) {
+                    self.mismatch(loc.to_string(), "required");
+                }
+
+                return Ok(());
+            }
+        };
+
+        if is_required(derived) && !is_required(base) {
+            self.mismatch(loc.to_string(), "required");
+        }
+
+        // Every media type old clients send must still be accepted.
+        for (media_type, base_media) in content(base) {
+            let loc = format!("{}/content/{}", loc, api::escape(media_type));
+
+            let derived_media =
+                match derived.pointer(&format!("/content/{}", api::escape(media_type))) {
+                    Some(derived_media) => derived_media,
+                    None => {
+                        self.mismatch(loc, "mediaType");
+                        continue;
+                    }
+                };
+
+            if let (Some(derived_schema), Some(base_schema)) =
+                (derived_media.get("schema"), base_media.get("schema"))
+            {
+                let loc = format!("{}/schema", loc);
+                self.check_schema(base_schema, derived_schema, Mode::Request, &loc)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_response(
+        &mut self,
+        status: &str,
+        derived: &'a Value,
+        base: &Operation<'a>,
+        loc: &str,
+    ) -> Result<(), Error> {
+        let base = match base.response(self.base, status)? {
+            Some(base) => base,
+            None => {
+                self.mismatch(loc.into(), "status");
+                return Ok(());
+            }
+        };
+
+        // Every media type the new server sends must be known to old clients.
+        for (media_type, derived_media) in content(derived) {
+            let loc = format!("{}/content/{}", loc, api::escape(media_type));
+
+            let base_media = match base.pointer(&format!("/content/{}", api::escape(media_type))) {
+                Some(base_media) => base_media,
+                None => {
+                    self.mismatch(loc, "mediaType");
+                    continue;
+                }
+            };
+
+            if let (Some(derived_schema), Some(base_schema)) =
+                (derived_media.get("schema"), base_media.get("schema"))
+            {
+                let loc = format!("{}/schema", loc);
+                self.check_schema(derived_schema, base_schema, Mode::Response, &loc)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the sent payload is accepted by the receiving side.
+    ///
+    /// Mismatches are reported relative to the schema in the derived document.
+    fn check_schema(
+        &mut self,
+        sent: &Value,
+        accepted: &Value,
+        mode: Mode,
+        loc: &str,
+    ) -> Result<(), Error> {
+        let (sent, accepted) = match mode {
+            Mode::Response => (self.derived.schema(sent)?, self.base.schema(accepted)?),
+            _ => (self.base.schema(sent)?, self.derived.schema(accepted)?),
+        };
+
+        let options = Options {
+            mode,
+            max_units: self.max_units,
+        };
+
+        api::check_schema(sent, accepted, &options, loc, &mut self.mismatches)
+    }
+
+    fn mismatch(&mut self, pointer: String, keyword: &'static str) {
+        self.mismatches.push(Mismatch { pointer, keyword });
+    }
+}
+
+struct Operation<'a> {
+    item: &'a Value,
+    op: &'a Value,
+}
+
+impl<'a> Operation<'a> {
+    fn new(item: &'a Value, op: &'a Value) -> Operation<'a> {
+        Operation { item, op }
+    }
+
+    /// Collects parameters of the path item overridden by ones of the operation.
+    ///
+    /// Parameters are paired with pointers to them, given the pointer to the operation.
+    fn parameters(&self, doc: &Document<'a>, loc: &str) -> Result<Vec<(String, &'a Value)>, Error> {
+        let item_loc = loc.rsplit_once('/').map_or("", |(item, _)| item);
+        let mut params: Vec<(String, &'a Value)> = Vec::new();
+
+        for (source, loc) in [(self.item, item_loc), (self.op, loc)] {
+            let list = match source.get("parameters").and_then(Value::as_array) {
+                Some(list) => list,
+                None => continue,
+            };
+
+            for (i, param) in list.iter().enumerate() {
+                let param = doc.follow(param)?;
+                params.retain(|(_, p)| !same_parameter(p, param));
+                params.push((format!("{}/parameters/{}", loc, i), param));
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Finds the response for the status, falling back to ranges (`2XX`) and `default`.
+    fn response(&self, doc: &Document<'a>, status: &str) -> Result<Option<&'a Value>, Error> {
+        let responses = match self.op.get("responses").and_then(Value::as_object) {
+            Some(responses) => responses,
+            None => return Ok(None),
+        };
+
+        let range = status.get(..1).map(|c| format!("{}XX", c));
+
+        let response = responses
+            .get(status)
+            .or_else(|| range.and_then(|r| responses.get(&r)))
+            .or_else(|| responses.get("default"));
+
+        response.map(|r| doc.follow(r)).transpose()
+    }
+}
+
+/// Parameters are identified by their name and location.
+fn same_parameter(a: &Value, b: &Value) -> bool {
+    a.get("name") == b.get("name") && a.get("in") == b.get("in")
+}
+
+fn is_required(value: &Value) -> bool {
+    value.get("in").and_then(Value::as_str) == Some("path")
+        || value.get("required").and_then(Value::as_bool) == Some(true)
+}
+
+fn parameter_schema(param: &Value) -> Option<&Value> {
+    param.get("schema").or_else(|| {
+        content(param)
+            .into_iter()
+            .next()
+            .and_then(|(_, media)| media.get("schema"))
+    })
+}
+
+fn content(value: &Value) -> Vec<(&str, &Value)> {
+    api::entries(value.get("content"))
+}
+
+/// Replaces names of template parameters: `/pets/{id}` and `/pets/{petId}` are the same path.
+fn template(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut in_param = false;
+
+    for c in path.chars() {
+        match c {
+            '{' => {
+                in_param = true;
+                out.push_str("{}");
+            }
+            '}' => in_param = false,
+            _ if !in_param => out.push(c),
+            _ => {}
+        }
+    }
+
+    out
+}
@@ -0,0 +1,7 @@
+use super::*;
+
+#[test]
+fn it_should_pair_paths_regardless_of_parameter_names() {
+    assert_eq!(template("/pets/{id}"), template("/pets/{petId}"));
+    assert_ne!(template("/pets/{id}"), template("/pets/{id}/owner"));
+}
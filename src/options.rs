@@ -0,0 +1,20 @@
+use mode::Mode;
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub mode: Mode,
+    /// The maximum number of units a schema can be spread into.
+    ///
+    /// Spreading is exponential in the worst case, so checking fails with
+    /// `Error::TooManyUnits` instead of exhausting memory.
+    pub max_units: usize,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            mode: Mode::Strict,
+            max_units: 10_000,
+        }
+    }
+}
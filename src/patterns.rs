@@ -0,0 +1,25 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use schema::RcStr;
+
+/// Compiles regular expressions once and keeps them for later matches.
+#[derive(Default)]
+pub struct Patterns {
+    regexes: RefCell<HashMap<RcStr, Option<Regex>>>,
+}
+
+impl Patterns {
+    /// Checks whether the string matches the pattern; invalid patterns match nothing.
+    pub fn is_match(&self, pattern: &RcStr, string: &str) -> bool {
+        let mut regexes = self.regexes.borrow_mut();
+
+        let regex = regexes
+            .entry(pattern.clone())
+            .or_insert_with(|| Regex::new(pattern).ok());
+
+        regex.as_ref().is_some_and(|regex| regex.is_match(string))
+    }
+}
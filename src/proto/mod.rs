@@ -0,0 +1,228 @@
+//! Conversion of proto3 messages into JSON Schemas of their canonical JSON encoding.
+//!
+//! All fields are optional, because default values are omitted. Members of a `oneof` become
+//! ordinary optional fields, since exclusivity can't be expressed without `not`.
+
+use serde_json::{Map, Value};
+
+use api;
+use error::Error;
+use schema::Schema;
+
+use self::parser::{Field, FieldType, File};
+
+mod parser;
+#[cfg(test)]
+mod spec;
+
+/// Converts the message of the `.proto` source into a JSON Schema.
+///
+/// The message is named either fully qualified or relative to the package.
+pub fn convert(source: &str, message: &str) -> Result<Schema, Error> {
+    let file = parser::parse(source)?;
+    let converter = Converter { file: &file };
+
+    let root = converter
+        .resolve(message.trim_start_matches('.'), "")
+        .or_else(|| {
+            file.messages
+                .iter()
+                .map(|m| m.name.as_str())
+                .find(|name| name.rsplit('.').next() == Some(message))
+        })
+        .filter(|name| file.messages.iter().any(|m| m.name == *name))
+        .ok_or_else(|| Error::UnresolvedRef(message.into()))?;
+
+    let mut definitions = Map::new();
+
+    for message in &file.messages {
+        definitions.insert(message.name.clone(), converter.convert_message(message)?);
+    }
+
+    for enum_ in &file.enums {
+        definitions.insert(
+            enum_.name.clone(),
+            json!({"type": "string", "enum": enum_.values}),
+        );
+    }
+
+    let schema = json!({
+        "$ref": format!("#/definitions/{}", api::escape(root)),
+        "definitions": definitions,
+    });
+
+    Ok(::serde_json::from_value(schema)?)
+}
+
+struct Converter<'a> {
+    file: &'a File,
+}
+
+impl<'a> Converter<'a> {
+    fn convert_message(&self, message: &parser::Message) -> Result<Value, Error> {
+        let mut properties = Map::new();
+
+        for field in &message.fields {
+            let name = field
+                .json_name
+                .clone()
+                .unwrap_or_else(|| camel_case(&field.name));
+
+            properties.insert(name, self.convert_field(field, &message.name)?);
+        }
+
+        Ok(json!({"type": "object", "properties": properties}))
+    }
+
+    fn convert_field(&self, field: &Field, scope: &str) -> Result<Value, Error> {
+        match field.type_ {
+            FieldType::Map(ref key, ref value) => {
+                let value = self.convert_type(value, scope)?;
+                let mut schema = json!({"type": "object", "additionalProperties": value});
+
+                // Keys are always strings in JSON, so only their format is checked.
+                let names = match key.as_str() {
+                    "string" => None,
+                    "bool" => Some(json!({"enum": ["true", "false"]})),
+                    _ => Some(json!({"pattern": "^-?[0-9]+$"})),
+                };
+
+                if let Some(names) = names {
+                    schema["propertyNames"] = names;
+                }
+
+                Ok(schema)
+            }
+            FieldType::Named(ref type_) => {
+                let schema = self.convert_type(type_, scope)?;
+
+                if field.repeated {
+                    Ok(json!({"type": "array", "items": schema}))
+                } else {
+                    Ok(schema)
+                }
+            }
+        }
+    }
+
+    fn convert_type(&self, type_: &str, scope: &str) -> Result<Value, Error> {
+        if let Some(schema) = convert_scalar(type_) {
+            return Ok(schema);
+        }
+
+        if let Some(schema) = convert_well_known(type_.trim_start_matches('.')) {
+            return Ok(schema);
+        }
+
+        let name = self
+            .resolve(type_, scope)
+            .ok_or_else(|| Error::UnresolvedRef(type_.into()))?;
+
+        Ok(json!({ "$ref": format!("#/definitions/{}", api::escape(name)) }))
+    }
+
+    /// Resolves the type name, looking it up from the innermost scope outwards.
+    fn resolve(&self, type_: &str, scope: &str) -> Option<&'a str> {
+        let find = |name: &str| {
+            let messages = self.file.messages.iter().map(|m| m.name.as_str());
+            let enums = self.file.enums.iter().map(|e| e.name.as_str());
+            messages.chain(enums).find(|n| *n == name)
+        };
+
+        if let Some(name) = type_.strip_prefix('.') {
+            return find(name);
+        }
+
+        let mut scope = scope;
+
+        loop {
+            let candidate = if scope.is_empty() {
+                type_.to_owned()
+            } else {
+                format!("{}.{}", scope, type_)
+            };
+
+            if let Some(name) = find(&candidate) {
+                return Some(name);
+            }
+
+            if scope.is_empty() {
+                return None;
+            }
+
+            scope = scope.rsplit_once('.').map_or("", |(outer, _)| outer);
+        }
+    }
+}
+
+fn convert_scalar(type_: &str) -> Option<Value> {
+    let schema = match type_ {
+        "int32" | "sint32" | "sfixed32" => {
+            json!({"type": "integer", "minimum": i32::MIN, "maximum": i32::MAX})
+        }
+        "uint32" | "fixed32" => json!({"type": "integer", "minimum": 0, "maximum": u32::MAX}),
+        // 64-bit integers are written as strings to not lose precision.
+        "int64" | "sint64" | "sfixed64" => json!({"type": "string", "pattern": "^-?[0-9]+$"}),
+        "uint64" | "fixed64" => json!({"type": "string", "pattern": "^[0-9]+$"}),
+        "float" | "double" => json!({
+            "anyOf": [
+                {"type": "number"},
+                {"type": "string", "enum": ["NaN", "Infinity", "-Infinity"]},
+            ],
+        }),
+        "bool" => json!({"type": "boolean"}),
+        "string" => json!({"type": "string"}),
+        "bytes" => json!({"type": "string", "contentEncoding": "base64"}),
+        _ => return None,
+    };
+
+    Some(schema)
+}
+
+/// Converts well-known types, which have special JSON representations.
+fn convert_well_known(type_: &str) -> Option<Value> {
+    let schema = match type_.strip_prefix("google.protobuf.")? {
+        "Timestamp" => json!({"type": "string", "format": "date-time"}),
+        "Duration" => json!({"type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?s$"}),
+        "FieldMask" => json!({"type": "string"}),
+        "Struct" | "Empty" => json!({"type": "object"}),
+        "ListValue" => json!({"type": "array"}),
+        "Value" => json!({}),
+        "NullValue" => json!({"type": "null"}),
+        "Any" => json!({
+            "type": "object",
+            "properties": {"@type": {"type": "string"}},
+            "required": ["@type"],
+        }),
+        "DoubleValue" | "FloatValue" => convert_scalar("double")?,
+        "Int64Value" => convert_scalar("int64")?,
+        "UInt64Value" => convert_scalar("uint64")?,
+        "Int32Value" => convert_scalar("int32")?,
+        "UInt32Value" => convert_scalar("uint32")?,
+        "BoolValue" => convert_scalar("bool")?,
+        "StringValue" => convert_scalar("string")?,
+        "BytesValue" => convert_scalar("bytes")?,
+        _ => return None,
+    };
+
+    Some(schema)
+}
+
+/// Converts a field name the way `protoc` derives `json_name`.
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
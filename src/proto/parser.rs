@@ -0,0 +1,370 @@
+//! A parser of the subset of proto3 describing data: messages, enums, fields, maps and oneofs.
+//!
+//! Services, options and imports are skipped.
+
+use error::Error;
+
+pub struct File {
+    pub messages: Vec<Message>,
+    pub enums: Vec<Enum>,
+}
+
+pub struct Message {
+    /// The fully qualified name without the leading dot.
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+pub struct Field {
+    pub name: String,
+    pub json_name: Option<String>,
+    pub type_: FieldType,
+    pub repeated: bool,
+}
+
+pub enum FieldType {
+    Named(String),
+    Map(String, String),
+}
+
+pub struct Enum {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifiers (possibly dotted) and numbers.
+    Word(String),
+    Str(String),
+    Sym(char),
+}
+
+pub fn parse(source: &str) -> Result<File, Error> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        file: File {
+            messages: Vec::new(),
+            enums: Vec::new(),
+        },
+    };
+
+    parser.parse_file()?;
+    Ok(parser.file)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    file: File,
+}
+
+impl Parser {
+    fn parse_file(&mut self) -> Result<(), Error> {
+        let mut package = String::new();
+
+        while let Some(token) = self.next() {
+            match token {
+                Token::Word(ref w) if w == "syntax" => {
+                    self.expect('=')?;
+
+                    match self.next() {
+                        Some(Token::Str(ref s)) if s == "proto3" => {}
+                        Some(Token::Str(_)) => return Err(Error::Unsupported("proto2")),
+                        _ => return Err(invalid("expected syntax version")),
+                    }
+
+                    self.expect(';')?;
+                }
+                Token::Word(ref w) if w == "package" => {
+                    package = self.word()?;
+                    self.expect(';')?;
+                }
+                Token::Word(ref w) if w == "message" => self.parse_message(&package)?,
+                Token::Word(ref w) if w == "enum" => self.parse_enum(&package)?,
+                Token::Sym(';') => {}
+                _ => self.skip_statement(),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_message(&mut self, scope: &str) -> Result<(), Error> {
+        let name = qualify(scope, &self.word()?);
+        self.expect('{')?;
+
+        let mut fields = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some(Token::Sym('}')) => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(Token::Sym(';')) => self.pos += 1,
+                Some(Token::Word(w)) => match w.as_str() {
+                    "message" => {
+                        self.pos += 1;
+                        self.parse_message(&name)?;
+                    }
+                    "enum" => {
+                        self.pos += 1;
+                        self.parse_enum(&name)?;
+                    }
+                    "oneof" => {
+                        self.pos += 1;
+                        self.word()?;
+                        self.expect('{')?;
+
+                        // Members of oneofs are ordinary fields in JSON.
+                        while self.peek() != Some(&Token::Sym('}')) {
+                            match self.peek() {
+                                Some(Token::Word(w)) if w == "option" => self.skip_statement(),
+                                Some(Token::Sym(';')) => self.pos += 1,
+                                _ => fields.push(self.parse_field()?),
+                            }
+                        }
+
+                        self.expect('}')?;
+                    }
+                    "option" | "reserved" | "extensions" => self.skip_statement(),
+                    "extend" | "group" | "required" => return Err(Error::Unsupported("proto2")),
+                    _ => fields.push(self.parse_field()?),
+                },
+                _ => return Err(invalid("unexpected end of message")),
+            }
+        }
+
+        self.file.messages.push(Message { name, fields });
+        Ok(())
+    }
+
+    fn parse_field(&mut self) -> Result<Field, Error> {
+        let mut repeated = false;
+        let mut word = self.word()?;
+
+        if word == "repeated" || word == "optional" {
+            repeated = word == "repeated";
+            word = self.word()?;
+        }
+
+        let type_ = if word == "map" {
+            self.expect('<')?;
+            let key = self.word()?;
+            self.expect(',')?;
+            let value = self.word()?;
+            self.expect('>')?;
+            FieldType::Map(key, value)
+        } else {
+            FieldType::Named(word)
+        };
+
+        let name = self.word()?;
+        self.expect('=')?;
+        self.word()?;
+
+        let json_name = if self.peek() == Some(&Token::Sym('[')) {
+            self.pos += 1;
+            self.parse_options()?
+        } else {
+            None
+        };
+
+        self.expect(';')?;
+
+        Ok(Field {
+            name,
+            json_name,
+            type_,
+            repeated,
+        })
+    }
+
+    /// Parses field options after the opening bracket, returning `json_name` if it's set.
+    ///
+    /// Other options, e.g. custom `(validate.rules).string.min_len = 1`, are skipped.
+    fn parse_options(&mut self) -> Result<Option<String>, Error> {
+        let mut json_name = None;
+        let mut option = Vec::new();
+        let mut depth = 0;
+
+        loop {
+            let token = self
+                .next()
+                .ok_or_else(|| invalid("unexpected end of options"))?;
+
+            match token {
+                Token::Sym('[') | Token::Sym('(') | Token::Sym('{') => depth += 1,
+                Token::Sym(']') | Token::Sym(',') if depth == 0 => {
+                    if let [Token::Word(ref name), Token::Sym('='), Token::Str(ref value)] =
+                        option[..]
+                    {
+                        if name == "json_name" {
+                            json_name = Some(value.clone());
+                        }
+                    }
+
+                    if token == Token::Sym(']') {
+                        return Ok(json_name);
+                    }
+
+                    option.clear();
+                    continue;
+                }
+                Token::Sym(']') | Token::Sym(')') | Token::Sym('}') => depth -= 1,
+                _ => {}
+            }
+
+            option.push(token);
+        }
+    }
+
+    fn parse_enum(&mut self, scope: &str) -> Result<(), Error> {
+        let name = qualify(scope, &self.word()?);
+        self.expect('{')?;
+
+        let mut values = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some(Token::Sym('}')) => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(Token::Sym(';')) => self.pos += 1,
+                Some(Token::Word(w)) if w == "option" || w == "reserved" => self.skip_statement(),
+                Some(Token::Word(_)) => {
+                    values.push(self.word()?);
+                    self.skip_statement();
+                }
+                _ => return Err(invalid("unexpected end of enum")),
+            }
+        }
+
+        self.file.enums.push(Enum { name, values });
+        Ok(())
+    }
+
+    /// Skips tokens up to `;` or a balanced `{ ... }` block.
+    fn skip_statement(&mut self) {
+        let mut depth = 0;
+
+        while let Some(token) = self.next() {
+            match token {
+                Token::Sym('{') => depth += 1,
+                Token::Sym('}') => {
+                    depth -= 1;
+
+                    if depth <= 0 {
+                        return;
+                    }
+                }
+                Token::Sym(';') if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<String, Error> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(word),
+            _ => Err(invalid("expected an identifier")),
+        }
+    }
+
+    fn expect(&mut self, sym: char) -> Result<(), Error> {
+        match self.next() {
+            Some(Token::Sym(s)) if s == sym => Ok(()),
+            _ => Err(invalid(&format!("expected `{}`", sym))),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+
+                loop {
+                    match chars.next() {
+                        Some('/') if prev == '*' => break,
+                        Some(c) => prev = c,
+                        None => return Err(invalid("unterminated comment")),
+                    }
+                }
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.extend(chars.next()),
+                        Some(q) if q == c => break,
+                        Some(c) => value.push(c),
+                        None => return Err(invalid("unterminated string")),
+                    }
+                }
+
+                tokens.push(Token::Str(value));
+            }
+            _ if is_word(c) => {
+                let mut word = c.to_string();
+
+                while let Some(&c) = chars.peek() {
+                    if !is_word(c) {
+                        break;
+                    }
+
+                    word.push(c);
+                    chars.next();
+                }
+
+                tokens.push(Token::Word(word));
+            }
+            _ => tokens.push(Token::Sym(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || c == '-' || c == '+'
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.into()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+pub fn invalid(reason: &str) -> Error {
+    Error::InvalidSchema(format!("invalid proto: {}", reason))
+}
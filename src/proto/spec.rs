@@ -0,0 +1,122 @@
+use super::*;
+
+const SOURCE: &str = r#"
+    syntax = "proto3";
+    package shop.v1;
+
+    import "google/protobuf/timestamp.proto";
+    option go_package = "shop/v1";
+
+    /* An order placed by a customer. */
+    message Order {
+        enum Status {
+            STATUS_UNSPECIFIED = 0;
+            STATUS_PAID = 1 [deprecated = true];
+        }
+
+        string order_id = 1;
+        repeated Item items = 2;
+        map<string, int32> counts = 3;
+        Status status = 4;
+        google.protobuf.Timestamp created_at = 5;
+        oneof payment {
+            string card = 6;
+            string voucher = 7 [json_name = "coupon"];
+        }
+        reserved 8, 9;
+    }
+
+    message Item {
+        uint64 sku = 1;
+    }
+
+    service Shop {
+        rpc Place(Order) returns (Order) { option idempotency_level = IDEMPOTENT; }
+    }
+"#;
+
+fn message(name: &str) -> Value {
+    let file = parser::parse(SOURCE).unwrap();
+    let converter = Converter { file: &file };
+    let message = file.messages.iter().find(|m| m.name == name).unwrap();
+    converter.convert_message(message).unwrap()
+}
+
+#[test]
+fn it_should_name_fields_in_camel_case() {
+    assert_eq!(camel_case("created_at"), "createdAt");
+    assert_eq!(camel_case("id"), "id");
+}
+
+#[test]
+fn it_should_convert_messages() {
+    let order = message("shop.v1.Order");
+    let properties = order["properties"].as_object().unwrap();
+
+    let mut names = properties.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+
+    assert_eq!(
+        names,
+        [
+            "card",
+            "counts",
+            "coupon",
+            "createdAt",
+            "items",
+            "orderId",
+            "status"
+        ]
+    );
+
+    assert_eq!(
+        properties["items"],
+        json!({"type": "array", "items": {"$ref": "#/definitions/shop.v1.Item"}})
+    );
+
+    assert_eq!(
+        properties["status"],
+        json!({"$ref": "#/definitions/shop.v1.Order.Status"})
+    );
+
+    assert_eq!(
+        properties["createdAt"],
+        json!({"type": "string", "format": "date-time"})
+    );
+}
+
+#[test]
+fn it_should_write_64_bit_integers_as_strings() {
+    assert_eq!(
+        message("shop.v1.Item")["properties"]["sku"],
+        json!({"type": "string", "pattern": "^[0-9]+$"})
+    );
+}
+
+#[test]
+fn it_should_reject_proto2() {
+    match parser::parse("syntax = \"proto2\";") {
+        Err(Error::Unsupported("proto2")) => {}
+        res => panic!("unexpected result: {}", res.is_ok()),
+    }
+}
+
+#[test]
+fn it_should_skip_custom_options() {
+    let file = parser::parse(
+        r#"
+        syntax = "proto3";
+
+        message User {
+            string name = 1 [(validate.rules).string.min_len = 1, json_name = "fullName"];
+            repeated string tags = 2 [(validate.rules).repeated = {min_items: 1, items: {string: {min_len: 1}}}];
+        }
+        "#,
+    )
+    .unwrap();
+
+    let fields = &file.messages[0].fields;
+    assert_eq!(fields[0].json_name, Some("fullName".into()));
+    assert_eq!(fields[1].name, "tags");
+    assert_eq!(fields[1].json_name, None);
+}
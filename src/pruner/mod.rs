@@ -0,0 +1,63 @@
+use interner::Interner;
+use matcher::Matcher;
+use schema::Rc_;
+use unit::{RcUnit, Unit};
+
+#[cfg(test)]
+mod spec;
+
+/// Removes units subsumed by other units of the same disjunction, including nested ones.
+///
+/// The matcher must be strict, because relaxed modes aren't transitive.
+pub fn prune(units: Vec<RcUnit>, interner: &mut Interner, matcher: &Matcher) -> Vec<RcUnit> {
+    let units = units
+        .into_iter()
+        .map(|unit| prune_nested(unit, interner, matcher))
+        .collect::<Vec<_>>();
+
+    let mut res = Vec::with_capacity(units.len());
+
+    for (i, unit) in units.iter().enumerate() {
+        // Equivalent units subsume each other, so only the first one is kept.
+        let subsumed = units.iter().enumerate().any(|(j, other)| {
+            i != j && matcher.subtype(unit, other) && (j < i || !matcher.subtype(other, unit))
+        });
+
+        if !subsumed {
+            res.push(unit.clone());
+        }
+    }
+
+    res
+}
+
+/// Prunes nested disjunctions of the unit, keeping it untouched if nothing is removed.
+///
+/// The unit is cloned only if something is, since that's rare.
+pub fn prune_nested(unit: RcUnit, interner: &mut Interner, matcher: &Matcher) -> RcUnit {
+    let pruned = unit
+        .nested()
+        .map(|variants| prune(variants.clone(), interner, matcher))
+        .collect::<Vec<_>>();
+
+    let changed = pruned.iter().zip(unit.nested()).any(|(res, variants)| {
+        res.len() != variants.len()
+            || res
+                .iter()
+                .zip(variants.iter())
+                .any(|(r, v)| !Rc_::ptr_eq(r, v))
+    });
+
+    if !changed {
+        return unit;
+    }
+
+    // A cloned map iterates in the same order, so variants are put back where they were.
+    let mut unit = Unit::clone(&unit);
+
+    for (variants, res) in unit.nested_mut().zip(pruned) {
+        *variants = res;
+    }
+
+    interner.intern(unit.into())
+}
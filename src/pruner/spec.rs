@@ -0,0 +1,61 @@
+use super::*;
+
+use mode::Mode;
+use schema::{RcStr, Type};
+use unit::Point;
+
+fn typed(type_: Type) -> RcUnit {
+    Unit {
+        type_: Some(type_),
+        ..Unit::default()
+    }
+    .into()
+}
+
+fn run(units: Vec<RcUnit>) -> Vec<RcUnit> {
+    prune(units, &mut Interner::default(), &Matcher::new(Mode::Strict))
+}
+
+#[test]
+fn it_should_remove_subsumed_units() {
+    let res = run(vec![typed(Type::Integer), typed(Type::Number)]);
+    assert_eq!(res, vec![typed(Type::Number)]);
+}
+
+#[test]
+fn it_should_keep_incomparable_units() {
+    let res = run(vec![typed(Type::String), typed(Type::Number)]);
+    assert_eq!(res, vec![typed(Type::String), typed(Type::Number)]);
+}
+
+#[test]
+fn it_should_keep_one_of_equivalent_units() {
+    let res = run(vec![typed(Type::Number), typed(Type::Number)]);
+    assert_eq!(res, vec![typed(Type::Number)]);
+}
+
+#[test]
+fn it_should_prune_nested_variants() {
+    let bounded = Unit {
+        maximum: Some(Point::inc(10.)),
+        ..Unit::default()
+    };
+
+    let unit = Unit {
+        properties: vec![(
+            RcStr::from("a"),
+            vec![bounded.into(), Unit::default().into()],
+        )]
+        .into_iter()
+        .collect(),
+        ..Unit::default()
+    };
+
+    let res = run(vec![unit.into()]);
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(
+        res[0].properties[&RcStr::from("a")],
+        vec![RcUnit::from(Unit::default())]
+    );
+}
@@ -0,0 +1,224 @@
+//! Generation of example instances of units.
+//!
+//! Samples are the simplest values meeting the bounds of a unit, e.g. the shortest string
+//! or the least number. Patterns and uniqueness aren't taken into account, so samples
+//! must be checked with the validator before they're shown.
+
+use serde_json::{Map, Value};
+
+use schema::{number, Type};
+use unit::{Point, Unit, Variants};
+
+#[cfg(test)]
+mod spec;
+
+/// How deep nested units are sampled, so recursive structures produce finite values.
+const MAX_DEPTH: usize = 16;
+
+/// Builds an instance of the unit, if a simple one exists.
+pub fn sample(unit: &Unit) -> Option<Value> {
+    sample_in(unit, 0)
+}
+
+fn sample_in(unit: &Unit, depth: usize) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    if let Some(ref const_) = unit.const_ {
+        return Some(Value::clone(const_));
+    }
+
+    match unit.type_.unwrap_or_else(|| guess(unit)) {
+        Type::Null => Some(Value::Null),
+        Type::Boolean => Some(Value::Bool(true)),
+        Type::Integer => sample_number(unit, true),
+        Type::Number => sample_number(unit, false),
+        Type::String => sample_string(unit),
+        Type::Array => sample_array(unit, depth),
+        Type::Object => sample_object(unit, depth),
+    }
+}
+
+/// Picks the type restricted by keywords of the unit.
+fn guess(unit: &Unit) -> Type {
+    if !unit.required.is_empty()
+        || !unit.properties.is_empty()
+        || unit.min_properties.is_some()
+        || unit.max_properties.is_some()
+    {
+        Type::Object
+    } else if unit.items.is_some()
+        || unit.contains.is_some()
+        || !unit.tuple.is_empty()
+        || unit.min_items.is_some()
+        || unit.max_items.is_some()
+    {
+        Type::Array
+    } else if unit.min_length.is_some()
+        || unit.max_length.is_some()
+        || !unit.pattern.is_empty()
+        || unit.format.is_some()
+    {
+        Type::String
+    } else if unit.minimum.is_some() || unit.maximum.is_some() || unit.multiple_of.is_some() {
+        Type::Number
+    } else {
+        Type::Null
+    }
+}
+
+/// Picks the least number above the minimum, or the greatest one below the maximum.
+fn sample_number(unit: &Unit, integer: bool) -> Option<Value> {
+    let step = match (unit.multiple_of, integer) {
+        (Some(m), _) => Some(m),
+        (None, true) => Some(1.),
+        (None, false) => None,
+    };
+
+    let value = match (unit.minimum, unit.maximum) {
+        (Some(min), max) => above(min, max, step),
+        (None, Some(max)) => below(max, step),
+        (None, None) => 0.,
+    };
+
+    if !value.is_finite() {
+        return None;
+    }
+
+    Some(number(value))
+}
+
+#[allow(clippy::float_cmp)]
+fn above(min: Point, max: Option<Point>, step: Option<f64>) -> f64 {
+    match step {
+        Some(step) => {
+            let value = (min.value / step).ceil() * step;
+
+            if value == min.value && !min.inclusive {
+                value + step
+            } else {
+                value
+            }
+        }
+        None if min.inclusive => min.value,
+        None => match max {
+            Some(max) => (min.value + max.value) / 2.,
+            None => min.value + 1.,
+        },
+    }
+}
+
+#[allow(clippy::float_cmp)]
+fn below(max: Point, step: Option<f64>) -> f64 {
+    match step {
+        Some(step) => {
+            let value = (max.value / step).floor() * step;
+
+            if value == max.value && !max.inclusive {
+                value - step
+            } else {
+                value
+            }
+        }
+        None if max.inclusive => max.value,
+        None => max.value - 1.,
+    }
+}
+
+fn sample_string(unit: &Unit) -> Option<Value> {
+    if let Some(sample) = unit.format.as_ref().and_then(|f| format(f)) {
+        return Some(sample.into());
+    }
+
+    let length = unit.min_length.unwrap_or(0) as usize;
+    Some("a".repeat(length).into())
+}
+
+fn format(format: &str) -> Option<&'static str> {
+    let sample = match format {
+        "date-time" => "1970-01-01T00:00:00Z",
+        "date" => "1970-01-01",
+        "time" => "00:00:00Z",
+        "email" => "user@example.com",
+        "hostname" => "example.com",
+        "ipv4" => "127.0.0.1",
+        "ipv6" => "::1",
+        "uri" => "https://example.com/",
+        "uuid" => "00000000-0000-0000-0000-000000000000",
+        _ => return None,
+    };
+
+    Some(sample)
+}
+
+fn sample_array(unit: &Unit, depth: usize) -> Option<Value> {
+    let contains = unit.contains.is_some() as usize;
+    let length = unit
+        .tuple
+        .len()
+        .max(contains)
+        .max(unit.min_items.unwrap_or(0) as usize);
+
+    let rest = if unit.tuple.is_empty() {
+        &unit.items
+    } else {
+        &unit.additional_items
+    };
+
+    let mut items = Vec::with_capacity(length);
+
+    for i in 0..length {
+        let item = match unit.tuple.get(i) {
+            Some(variants) => sample_any(variants, depth)?,
+            None if i == 0 && contains > 0 => sample_any(unit.contains.as_ref()?, depth)?,
+            None => match rest {
+                Some(variants) => sample_any(variants, depth)?,
+                None => Value::Null,
+            },
+        };
+
+        items.push(item);
+    }
+
+    Some(Value::Array(items))
+}
+
+fn sample_object(unit: &Unit, depth: usize) -> Option<Value> {
+    let mut props = Map::new();
+
+    let mut names = unit.required.iter().collect::<Vec<_>>();
+    names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+    for name in names {
+        let value = match (unit.properties.get(name), &unit.additional_props) {
+            (Some(variants), _) | (None, Some(variants)) => sample_any(variants, depth)?,
+            (None, None) => Value::Null,
+        };
+
+        props.insert(name.to_string(), value);
+    }
+
+    // Optional properties fill up the minimal count.
+    let min = unit.min_properties.unwrap_or(0) as usize;
+
+    if props.len() < min {
+        let mut optional = unit
+            .properties
+            .iter()
+            .filter(|(name, variants)| !variants.is_empty() && !props.contains_key(name.as_str()))
+            .collect::<Vec<_>>();
+
+        optional.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+
+        for (name, variants) in optional.into_iter().take(min - props.len()) {
+            props.insert(name.to_string(), sample_any(variants, depth)?);
+        }
+    }
+
+    Some(Value::Object(props))
+}
+
+fn sample_any(variants: &Variants, depth: usize) -> Option<Value> {
+    variants.iter().find_map(|u| sample_in(u, depth + 1))
+}
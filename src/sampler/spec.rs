@@ -0,0 +1,88 @@
+use super::*;
+
+use schema::RcStr;
+
+fn typed(type_: Type) -> Unit {
+    Unit {
+        type_: Some(type_),
+        ..Unit::default()
+    }
+}
+
+#[test]
+fn it_should_sample_numbers_within_bounds() {
+    let integer = Unit {
+        minimum: Some(Point::exc(10.)),
+        ..typed(Type::Integer)
+    };
+
+    let number = Unit {
+        minimum: Some(Point::exc(1.)),
+        maximum: Some(Point::exc(2.)),
+        ..typed(Type::Number)
+    };
+
+    let multiple = Unit {
+        maximum: Some(Point::inc(10.)),
+        multiple_of: Some(3.),
+        ..typed(Type::Integer)
+    };
+
+    assert_eq!(sample(&integer), Some(json!(11)));
+    assert_eq!(sample(&number), Some(json!(1.5)));
+    assert_eq!(sample(&multiple), Some(json!(9)));
+}
+
+#[test]
+fn it_should_sample_strings() {
+    let string = Unit {
+        min_length: Some(3),
+        ..typed(Type::String)
+    };
+
+    let date = Unit {
+        format: Some(RcStr::from("date")),
+        ..typed(Type::String)
+    };
+
+    assert_eq!(sample(&string), Some(json!("aaa")));
+    assert_eq!(sample(&date), Some(json!("1970-01-01")));
+}
+
+#[test]
+fn it_should_sample_required_properties() {
+    let unit = Unit {
+        required: vec![RcStr::from("id")].into_iter().collect(),
+        properties: vec![
+            (RcStr::from("id"), vec![typed(Type::Integer).into()]),
+            (RcStr::from("name"), vec![typed(Type::String).into()]),
+        ]
+        .into_iter()
+        .collect(),
+        ..Unit::default()
+    };
+
+    assert_eq!(sample(&unit), Some(json!({"id": 0})));
+}
+
+#[test]
+fn it_should_sample_contained_items() {
+    let unit = Unit {
+        contains: Some(vec![typed(Type::Boolean).into()]),
+        min_items: Some(2),
+        ..typed(Type::Array)
+    };
+
+    assert_eq!(sample(&unit), Some(json!([true, null])));
+}
+
+#[test]
+fn it_should_fail_without_variants() {
+    let unit = Unit {
+        required: vec![RcStr::from("id")].into_iter().collect(),
+        properties: vec![(RcStr::from("id"), Vec::new())].into_iter().collect(),
+        ..Unit::default()
+    };
+
+    assert_eq!(sample(&unit), None);
+}
@@ -1,15 +1,43 @@
 use std::collections::HashMap;
+use std::ops::Deref;
+#[cfg(not(feature = "parallel"))]
 use std::rc::Rc;
+use std::str::FromStr;
+#[cfg(feature = "parallel")]
+use std::sync::Arc as Rc;
 
-use serde_json::Value;
+use serde::Deserializer;
+use serde_json::{self, Value};
+
+use error::Error;
 
 pub type RcMixed = Rc_<Value>;
 pub type RcStr = Rc_<String>;
 
-#[allow(derive_hash_xor_eq)]
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Debug, Default, Hash, Clone, Eq, Deserialize)]
 pub struct Rc_<T>(Rc<T>);
 
+impl<T> Rc_<T> {
+    pub fn new(val: T) -> Rc_<T> {
+        Rc_(Rc::new(val))
+    }
+
+    pub fn ptr_eq(this: &Rc_<T>, other: &Rc_<T>) -> bool {
+        Rc::ptr_eq(&this.0, &other.0)
+    }
+
+    pub fn as_ptr(this: &Rc_<T>) -> *const T {
+        Rc::as_ptr(&this.0)
+    }
+}
+
+impl<T: Clone> Rc_<T> {
+    pub fn unwrap_or_clone(this: Rc_<T>) -> T {
+        Rc::try_unwrap(this.0).unwrap_or_else(|rc| (*rc).clone())
+    }
+}
+
 impl<T: Into<Value>> From<T> for RcMixed {
     fn from(val: T) -> RcMixed {
         Rc_(Rc::new(val.into()))
@@ -28,14 +56,30 @@ impl<T: PartialEq> PartialEq for Rc_<T> {
     }
 }
 
-#[derive(Debug, Deserialize)]
+impl<T> Deref for Rc_<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum Schema {
     Simple(bool),
     Complex(Box<Restrictions>),
 }
 
-#[derive(Debug, Deserialize)]
+impl FromStr for Schema {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Schema, Error> {
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct Restrictions {
     #[serde(rename = "$id")]
     pub id: Option<RcStr>,
@@ -45,7 +89,13 @@ pub struct Restrictions {
     pub schema: Option<RcStr>,
     pub title: Option<RcStr>,
     pub description: Option<RcStr>,
+    #[serde(default, deserialize_with = "present")]
     pub default: Option<RcMixed>,
+    #[serde(rename = "readOnly")]
+    pub read_only: Option<bool>,
+    #[serde(rename = "writeOnly")]
+    pub write_only: Option<bool>,
+    pub deprecated: Option<bool>,
     #[serde(rename = "multipleOf")]
     pub multiple_of: Option<f64>,
     pub maximum: Option<f64>,
@@ -64,7 +114,7 @@ pub struct Restrictions {
     pub items: Option<Items>,
     #[serde(rename = "maxItems")]
     pub max_items: Option<u32>,
-    #[serde(rename = "midItems")]
+    #[serde(rename = "minItems")]
     pub min_items: Option<u32>,
     #[serde(rename = "uniqueItems")]
     pub unique_items: Option<bool>,
@@ -75,7 +125,7 @@ pub struct Restrictions {
     pub required: Option<Vec<RcStr>>,
     #[serde(rename = "additionalProperties")]
     pub additional_properties: Option<Schema>,
-    #[serde(rename = "additionalNames")]
+    #[serde(rename = "propertyNames")]
     pub property_names: Option<Schema>,
     pub contains: Option<Schema>,
     pub format: Option<RcStr>,
@@ -86,7 +136,7 @@ pub struct Restrictions {
     pub dependencies: Option<HashMap<RcStr, Dependency>>,
     #[serde(rename = "enum")]
     pub enum_: Option<Vec<RcMixed>>,
-    #[serde(rename = "const")]
+    #[serde(rename = "const", default, deserialize_with = "present")]
     pub const_: Option<RcMixed>,
     #[serde(rename = "type")]
     pub type_: Option<Types>,
@@ -97,16 +147,35 @@ pub struct Restrictions {
     #[serde(rename = "oneOf")]
     pub one_of: Option<Vec<Schema>>,
     pub not: Option<Schema>,
+    #[serde(rename = "if")]
+    pub if_: Option<Schema>,
+    pub then: Option<Schema>,
+    #[serde(rename = "else")]
+    pub else_: Option<Schema>,
+}
+
+/// Writes integral numbers as integers, the way JSON documents usually do.
+pub fn number(value: f64) -> Value {
+    if value.fract() == 0. && value.abs() < 2f64.powi(53) {
+        Value::from(value as i64)
+    } else {
+        Value::from(value)
+    }
+}
+
+/// Keeps `null` as a value instead of treating it as a missing keyword.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<RcMixed>, D::Error> {
+    serde::Deserialize::deserialize(deserializer).map(Some)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum Types {
     One(Type),
     Any(Vec<Type>),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum Type {
     #[serde(rename = "object")]
     Object,
@@ -124,14 +193,14 @@ pub enum Type {
     Null,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum Items {
     Array(Schema),
     Tuple(Vec<Schema>),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum Dependency {
     Property(Vec<RcStr>),
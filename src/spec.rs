@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::Path;
+
+use super::*;
+
+fn fixtures() -> Vec<Schema> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut paths = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).unwrap().parse().ok())
+        .collect()
+}
+
+fn mismatches(res: Result<Verdict, Error>) -> Result<Vec<Mismatch>, String> {
+    match res {
+        Ok(Verdict::Success) => Ok(Vec::new()),
+        Ok(Verdict::Failure(mismatches)) => Ok(mismatches),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[test]
+fn it_should_check_in_parallel_like_sequentially() {
+    let schemas = fixtures();
+
+    for mode in &[Mode::Strict, Mode::Request, Mode::Response] {
+        let options = Options {
+            mode: *mode,
+            ..Options::default()
+        };
+
+        for derived in &schemas {
+            for base in &schemas {
+                let check = |by: CheckBatch| {
+                    mismatches(check_by(derived.clone(), base.clone(), &options, by))
+                };
+
+                assert_eq!(check(check_batch_in_parallel), check(check_batch));
+            }
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::iter;
+use std::rc::Rc;
+
+use regex::Regex;
+
+use error::Error;
+use merger;
+use schema::{Items, RcMixed, RcStr, Restrictions, Schema, Type, Types};
+use unit::{Names, Point, Props, RcUnit, Unit, Variants};
+use validator;
+
+#[cfg(test)]
+mod spec;
+
+type Units = Box<dyn Iterator<Item = Unit>>;
+
+/// Spreads the schema into units, failing if there are more than `max_units` ones.
+pub fn spread(schema: Schema, max_units: usize) -> Result<Vec<Unit>, Error> {
+    stream(schema, max_units)?.collect()
+}
+
+/// Spreads the schema lazily: units are produced only on demand.
+pub fn stream(schema: Schema, max_units: usize) -> Result<Stream, Error> {
+    // Keep the original schema to resolve `$ref`s against it.
+    let root = schema.clone();
+
+    let budget = Rc::new(Budget::new(max_units));
+
+    let mut spreader = Spreader {
+        root: &root,
+        resolving: Vec::new(),
+        max_units,
+        budget: budget.clone(),
+    };
+
+    Ok(Stream {
+        units: spreader.spread(schema)?,
+        left: max_units,
+        limit: max_units,
+        budget,
+    })
+}
+
+pub struct Stream {
+    units: Units,
+    left: usize,
+    limit: usize,
+    budget: Rc<Budget>,
+}
+
+impl Iterator for Stream {
+    type Item = Result<Unit, Error>;
+
+    fn next(&mut self) -> Option<Result<Unit, Error>> {
+        let unit = match self.units.next() {
+            Some(unit) => unit,
+            None => return self.budget.take_error().map(Err),
+        };
+
+        if self.left == 0 {
+            self.units = Box::new(iter::empty());
+            return Some(Err(Error::TooManyUnits(self.limit)));
+        }
+
+        self.left -= 1;
+        Some(Ok(unit))
+    }
+}
+
+/// Limits combinations rejected while multiplying disjunctions.
+///
+/// Rejected combinations aren't emitted, so the unit budget alone doesn't bound them.
+/// Iterators cannot return errors, so the one that stopped multiplying is kept here too.
+struct Budget {
+    left: Cell<usize>,
+    limit: usize,
+    error: RefCell<Option<Error>>,
+}
+
+impl Budget {
+    fn new(max_rejected: usize) -> Budget {
+        Budget {
+            left: Cell::new(max_rejected),
+            limit: max_rejected,
+            error: RefCell::new(None),
+        }
+    }
+
+    /// Charges for a rejected combination, returning false once the budget is exhausted.
+    fn reject(&self) -> bool {
+        match self.left.get() {
+            0 => {
+                self.fail(Error::TooManyUnits(self.limit));
+                false
+            }
+            left => {
+                self.left.set(left - 1);
+                true
+            }
+        }
+    }
+
+    fn fail(&self, err: Error) {
+        self.error.borrow_mut().get_or_insert(err);
+    }
+
+    fn is_failed(&self) -> bool {
+        self.error.borrow().is_some()
+    }
+
+    /// Reports the error once, so the stream ends after it.
+    fn take_error(&self) -> Option<Error> {
+        self.error.borrow_mut().take()
+    }
+}
+
+struct Spreader<'a> {
+    root: &'a Schema,
+    resolving: Vec<RcStr>,
+    max_units: usize,
+    budget: Rc<Budget>,
+}
+
+impl<'a> Spreader<'a> {
+    fn spread(&mut self, schema: Schema) -> Result<Units, Error> {
+        let res = match schema {
+            Schema::Complex(res) => *res,
+            Schema::Simple(true) => return Ok(Box::new(iter::once(Unit::default()))),
+            Schema::Simple(false) => return Ok(Box::new(iter::empty())),
+        };
+
+        // Other keywords are ignored next to `$ref`.
+        if let Some(reference) = res.ref_ {
+            return self.resolve(reference);
+        }
+
+        validate(&res)?;
+
+        // TODO: one_of
+        // TODO: dependencies
+        if res.one_of.is_some() {
+            return Err(Error::Unsupported("oneOf"));
+        }
+
+        // TODO: not
+        // Only `{"not": {}}` is supported, which is how schemas without units are exported.
+        if let Some(not) = res.not {
+            if self.spread_vec(not)? != [Unit::default()] {
+                return Err(Error::Unsupported("not"));
+            }
+
+            return Ok(Box::new(iter::empty()));
+        }
+
+        if res.dependencies.is_some() {
+            return Err(Error::Unsupported("dependencies"));
+        }
+
+        // Without `if` both branches are ignored, and without branches `if` is.
+        if res.if_.is_some() && (res.then.is_some() || res.else_.is_some()) {
+            return Err(Error::Unsupported("if"));
+        }
+
+        let mut unit = Unit {
+            multiple_of: res.multiple_of,
+            max_length: res.max_length,
+            min_length: res.min_length,
+            max_items: res.max_items,
+            min_items: res.min_items,
+            max_properties: res.max_properties,
+            min_properties: res.min_properties,
+            unique_items: res.unique_items.unwrap_or(false),
+            required: res
+                .required
+                .map_or_else(Names::new, |v| v.into_iter().collect()),
+            format: res.format,
+            const_: res.const_,
+            default: res.default,
+            read_only: res.read_only.unwrap_or(false),
+            write_only: res.write_only.unwrap_or(false),
+            deprecated: res.deprecated.unwrap_or(false),
+            ..Unit::default()
+        };
+
+        unit.maximum = match (&res.maximum, &res.exclusive_maximum) {
+            (Some(inc), Some(exc)) => Some(Point::inc(*inc).min(Point::exc(*exc))),
+            (Some(inc), None) => Some(Point::inc(*inc)),
+            (None, Some(exc)) => Some(Point::exc(*exc)),
+            (None, None) => None,
+        };
+
+        unit.minimum = match (&res.minimum, &res.exclusive_minimum) {
+            (Some(inc), Some(exc)) => Some(Point::inc(*inc).max(Point::exc(*exc))),
+            (Some(inc), None) => Some(Point::inc(*inc)),
+            (None, Some(exc)) => Some(Point::exc(*exc)),
+            (None, None) => None,
+        };
+
+        if let Some(pattern) = res.pattern {
+            unit.pattern.insert(pattern);
+        }
+
+        let types = match res.type_ {
+            Some(Types::One(type_)) => {
+                unit.type_ = Some(type_);
+                Vec::new()
+            }
+            Some(Types::Any(types)) => types,
+            None => Vec::new(),
+        };
+
+        // Nested subschemas keep their variants locally instead of multiplying the unit.
+        unit.additional_items = self.spread_opt(res.additional_items)?;
+        unit.additional_props = self.spread_opt(res.additional_properties)?;
+        unit.property_names = self.spread_opt(res.property_names)?;
+        unit.contains = self.spread_opt(res.contains)?;
+        unit.items = match res.items {
+            Some(Items::Array(schema)) => Some(self.spread_variants(schema)?),
+            Some(Items::Tuple(_)) => return Err(Error::Unsupported("tuple items")),
+            None => None,
+        };
+
+        unit.properties = self.spread_map(res.properties)?;
+        unit.pattern_props = self.spread_map(res.pattern_properties)?;
+
+        if !merger::satisfiable(&unit) {
+            return Ok(Box::new(iter::empty()));
+        }
+
+        let mut enums = res.enum_.unwrap_or_else(Vec::new);
+
+        // Next to `const` only equal values of `enum` are valid, which is the `const` itself.
+        if let Some(ref const_) = unit.const_ {
+            if !enums.is_empty() && !enums.iter().any(|value| validator::equal(value, const_)) {
+                return Ok(Box::new(iter::empty()));
+            }
+
+            enums.clear();
+        }
+        let any_of = self.spread_all(res.any_of)?;
+        let all_of = self.spread_all(res.all_of)?;
+
+        let units: Units = Box::new(iter::once(unit));
+
+        // Disjunctions are multiplied on demand, so only the current combination is in memory.
+        let units = spread_nested(units, types, save_type);
+        let units = spread_nested(units, enums, save_const);
+        let units = spread_any_of(units, any_of, self.budget.clone());
+        let units = spread_all_of(units, all_of, self.budget.clone());
+
+        Ok(units)
+    }
+
+    fn resolve(&mut self, reference: RcStr) -> Result<Units, Error> {
+        if self.resolving.contains(&reference) {
+            return Err(Error::Unsupported("recursive $ref"));
+        }
+
+        let target = lookup(self.root, &reference)
+            .ok_or_else(|| Error::UnresolvedRef(reference.to_string()))?
+            .clone();
+
+        self.resolving.push(reference);
+        let units = self.spread(target);
+        self.resolving.pop();
+
+        units
+    }
+
+    /// Spreads a subschema eagerly, because its units are reused for every combination.
+    fn spread_vec(&mut self, schema: Schema) -> Result<Vec<Unit>, Error> {
+        let units = bounded(self.spread(schema)?, self.max_units)?;
+
+        match self.budget.take_error() {
+            Some(err) => Err(err),
+            None => Ok(units),
+        }
+    }
+
+    fn spread_variants(&mut self, schema: Schema) -> Result<Variants, Error> {
+        let units = self.spread_vec(schema)?;
+        Ok(units.into_iter().map(RcUnit::from).collect())
+    }
+
+    fn spread_opt(&mut self, schema: Option<Schema>) -> Result<Option<Variants>, Error> {
+        schema
+            .map(|schema| self.spread_variants(schema))
+            .transpose()
+    }
+
+    fn spread_all(&mut self, schemas: Option<Vec<Schema>>) -> Result<Vec<Vec<Unit>>, Error> {
+        schemas
+            .unwrap_or_default()
+            .into_iter()
+            .map(|schema| self.spread_vec(schema))
+            .collect()
+    }
+
+    fn spread_map(&mut self, map: Option<HashMap<RcStr, Schema>>) -> Result<Props, Error> {
+        map.unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| Ok((key, self.spread_variants(value)?)))
+            .collect()
+    }
+}
+
+fn validate(res: &Restrictions) -> Result<(), Error> {
+    if res.multiple_of.is_some_and(|m| m <= 0.) {
+        return Err(Error::InvalidSchema(
+            "`multipleOf` must be greater than 0".into(),
+        ));
+    }
+
+    if res.enum_.as_ref().is_some_and(Vec::is_empty) {
+        return Err(Error::InvalidSchema("`enum` must not be empty".into()));
+    }
+
+    if res.all_of.as_ref().is_some_and(Vec::is_empty) {
+        return Err(Error::InvalidSchema("`allOf` must not be empty".into()));
+    }
+
+    if res.any_of.as_ref().is_some_and(Vec::is_empty) {
+        return Err(Error::InvalidSchema("`anyOf` must not be empty".into()));
+    }
+
+    let patterns = res
+        .pattern
+        .iter()
+        .chain(res.pattern_properties.iter().flat_map(|props| props.keys()));
+
+    for pattern in patterns {
+        if Regex::new(pattern).is_err() {
+            return Err(Error::InvalidRegex(pattern.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds a subschema by a local reference (`#` or `#/json/pointer`).
+fn lookup<'s>(root: &'s Schema, reference: &str) -> Option<&'s Schema> {
+    let pointer = reference.strip_prefix('#')?;
+
+    if pointer.is_empty() {
+        return Some(root);
+    }
+
+    let mut segments = pointer
+        .strip_prefix('/')?
+        .split('/')
+        .map(|s| s.replace("~1", "/").replace("~0", "~"));
+
+    let mut schema = root;
+
+    while let Some(keyword) = segments.next() {
+        let res = match schema {
+            Schema::Complex(res) => res,
+            Schema::Simple(_) => return None,
+        };
+
+        schema = match keyword.as_str() {
+            "definitions" => res.definitions.as_ref()?.get(&segments.next()?.into())?,
+            "properties" => res.properties.as_ref()?.get(&segments.next()?.into())?,
+            "patternProperties" => res
+                .pattern_properties
+                .as_ref()?
+                .get(&segments.next()?.into())?,
+            "items" => match res.items.as_ref()? {
+                Items::Array(schema) => schema,
+                Items::Tuple(schemas) => schemas.get(segments.next()?.parse::<usize>().ok()?)?,
+            },
+            "allOf" => res
+                .all_of
+                .as_ref()?
+                .get(segments.next()?.parse::<usize>().ok()?)?,
+            "anyOf" => res
+                .any_of
+                .as_ref()?
+                .get(segments.next()?.parse::<usize>().ok()?)?,
+            "oneOf" => res
+                .one_of
+                .as_ref()?
+                .get(segments.next()?.parse::<usize>().ok()?)?,
+            "additionalItems" => res.additional_items.as_ref()?,
+            "additionalProperties" => res.additional_properties.as_ref()?,
+            "propertyNames" => res.property_names.as_ref()?,
+            "contains" => res.contains.as_ref()?,
+            "not" => res.not.as_ref()?,
+            _ => return None,
+        };
+    }
+
+    Some(schema)
+}
+
+fn bounded(units: Units, max_units: usize) -> Result<Vec<Unit>, Error> {
+    let units = units.take(max_units + 1).collect::<Vec<_>>();
+
+    if units.len() > max_units {
+        return Err(Error::TooManyUnits(max_units));
+    }
+
+    Ok(units)
+}
+
+fn spread_nested<T: Clone + 'static>(
+    units: Units,
+    nested: Vec<T>,
+    save: fn(&mut Unit, T),
+) -> Units {
+    if nested.is_empty() {
+        return units;
+    }
+
+    let nested = Rc::new(nested);
+
+    Box::new(units.flat_map(move |unit| {
+        let nested = nested.clone();
+        let mut unit = Some(unit);
+
+        // The last variant takes the unit, so a single one doesn't clone it.
+        (0..nested.len()).map(move |i| {
+            let mut dst = if i + 1 < nested.len() {
+                unit.clone()
+            } else {
+                unit.take()
+            }
+            .unwrap();
+
+            save(&mut dst, nested[i].clone());
+            dst
+        })
+    }))
+}
+
+fn spread_any_of(units: Units, schemas: Vec<Vec<Unit>>, budget: Rc<Budget>) -> Units {
+    if schemas.is_empty() {
+        return units;
+    }
+
+    let variants = Rc::new(Iterator::flatten(schemas.into_iter()).collect::<Vec<_>>());
+
+    Box::new(units.flat_map(move |src| {
+        let variants = variants.clone();
+        let budget = budget.clone();
+
+        // TODO: remove unnecessary `dst` cloning.
+        (0..variants.len())
+            .map(move |i| {
+                if budget.is_failed() {
+                    return None;
+                }
+
+                let mut dst = variants[i].clone();
+
+                match merger::merge(&mut dst, &src) {
+                    Ok(true) => Some(Some(dst)),
+                    Ok(false) if budget.reject() => Some(None),
+                    Ok(false) => None,
+                    Err(err) => {
+                        budget.fail(err);
+                        None
+                    }
+                }
+            })
+            .take_while(Option::is_some)
+            .flatten()
+            .flatten()
+    }))
+}
+
+fn spread_all_of(units: Units, schemas: Vec<Vec<Unit>>, budget: Rc<Budget>) -> Units {
+    if schemas.is_empty() {
+        return units;
+    }
+
+    let lens = schemas.iter().map(Vec::len).collect::<Vec<_>>();
+    let schemas = Rc::new(schemas);
+
+    Box::new(units.flat_map(move |unit| {
+        let schemas = schemas.clone();
+        let budget = budget.clone();
+
+        Product::new(lens.clone())
+            .map(move |indices| {
+                if budget.is_failed() {
+                    return None;
+                }
+
+                let mut dst = unit.clone();
+
+                for (src, &i) in schemas.iter().zip(&indices) {
+                    match merger::merge(&mut dst, &src[i]) {
+                        Ok(true) => {}
+                        Ok(false) if budget.reject() => return Some(None),
+                        Ok(false) => return None,
+                        Err(err) => {
+                            budget.fail(err);
+                            return None;
+                        }
+                    }
+                }
+
+                Some(Some(dst))
+            })
+            .take_while(Option::is_some)
+            .flatten()
+            .flatten()
+    }))
+}
+
+/// A lazy cartesian product of variants, yielding an index into each list of them.
+///
+/// Variants are looked up by the indices, so combinations don't clone them.
+struct Product {
+    lens: Vec<usize>,
+    indices: Option<Vec<usize>>,
+}
+
+impl Product {
+    fn new(lens: Vec<usize>) -> Product {
+        let indices = if lens.contains(&0) {
+            None
+        } else {
+            Some(vec![0; lens.len()])
+        };
+
+        Product { lens, indices }
+    }
+}
+
+impl Iterator for Product {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let indices = self.indices.as_mut()?;
+        let item = indices.clone();
+
+        // Advance indices like an odometer.
+        let mut done = true;
+
+        for (i, len) in indices.iter_mut().zip(&self.lens).rev() {
+            *i += 1;
+
+            if *i < *len {
+                done = false;
+                break;
+            }
+
+            *i = 0;
+        }
+
+        if done {
+            self.indices = None;
+        }
+
+        Some(item)
+    }
+}
+
+fn save_type(dst: &mut Unit, type_: Type) {
+    dst.type_ = Some(type_);
+}
+
+fn save_const(dst: &mut Unit, const_: RcMixed) {
+    dst.const_ = Some(const_);
+}
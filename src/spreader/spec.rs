@@ -0,0 +1,100 @@
+use super::*;
+
+use serde_json;
+
+fn schema(json: &str) -> Schema {
+    serde_json::from_str(json).unwrap()
+}
+
+mod product {
+    use super::Product;
+
+    #[test]
+    fn it_should_combine_all_variants() {
+        let res = Product::new(vec![2, 1, 2]).collect::<Vec<_>>();
+
+        assert_eq!(
+            res,
+            vec![vec![0, 0, 0], vec![0, 0, 1], vec![1, 0, 0], vec![1, 0, 1]]
+        );
+    }
+
+    #[test]
+    fn it_should_be_empty_if_any_variant_is_empty() {
+        assert_eq!(Product::new(vec![2, 0]).count(), 0);
+    }
+}
+
+#[test]
+fn it_should_spread_type_arrays() {
+    let units = spread(schema(r#"{"type": ["integer", "string"]}"#), 10).unwrap();
+    assert_eq!(units.len(), 2);
+}
+
+#[test]
+fn it_should_spread_boolean_schemas() {
+    assert_eq!(spread(schema("true"), 10).unwrap(), [Unit::default()]);
+    assert!(spread(schema("false"), 10).unwrap().is_empty());
+
+    let units = spread(schema(r#"{"properties": {"a": false}}"#), 10).unwrap();
+    assert!(units[0].properties[&RcStr::from("a")].is_empty());
+}
+
+#[test]
+fn it_should_intersect_enum_with_const() {
+    let units = spread(schema(r#"{"const": 1, "enum": [1.0, 2]}"#), 10).unwrap();
+    assert_eq!(units.len(), 1);
+    assert_eq!(units[0].const_, Some(1.into()));
+
+    let units = spread(schema(r#"{"const": 2, "enum": [1, 3]}"#), 10).unwrap();
+    assert!(units.is_empty());
+}
+
+#[test]
+fn it_should_fail_if_too_many_units() {
+    let res = spread(schema(r#"{"type": ["integer", "string", "null"]}"#), 2);
+
+    match res {
+        Err(Error::TooManyUnits(2)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_keep_variants_inside_properties() {
+    let props = (0..30)
+        .map(|i| format!(r#""p{}": {{"type": ["integer", "string"]}}"#, i))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let json = format!(r#"{{"properties": {{{}}}}}"#, props);
+    let units = spread(schema(&json), 2).unwrap();
+
+    assert_eq!(units.len(), 1);
+    assert!(units[0].properties.values().all(|p| p.len() == 2));
+}
+
+#[test]
+fn it_should_stream_units_lazily() {
+    let branches = (0..30)
+        .map(|i| {
+            format!(
+                r#"{{"anyOf": [{{"minimum": {0}}}, {{"maximum": {0}}}]}}"#,
+                i
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let json = format!(r#"{{"allOf": [{}]}}"#, branches);
+    let mut units = stream(schema(&json), 5).unwrap();
+
+    assert_eq!(units.by_ref().take(5).filter(Result::is_ok).count(), 5);
+
+    match units.next() {
+        Some(Err(Error::TooManyUnits(5))) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    assert!(units.next().is_none());
+}
@@ -0,0 +1,625 @@
+//! Conversion of TypeScript declarations into JSON Schemas of values they describe.
+//!
+//! Only the subset describing JSON data is supported: interfaces, type aliases, unions,
+//! intersections, literal types, optional properties, arrays and `Record`.
+
+use serde_json::{Map, Value};
+
+use api;
+use error::Error;
+use schema::{number, Schema};
+
+#[cfg(test)]
+mod spec;
+
+/// Converts the declared type of the source into a JSON Schema.
+pub fn convert(source: &str, name: &str) -> Result<Schema, Error> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        definitions: Map::new(),
+        references: Vec::new(),
+        records: Vec::new(),
+    };
+
+    parser.parse_declarations()?;
+
+    let missing = parser
+        .references
+        .iter()
+        .map(String::as_str)
+        .chain(Some(name))
+        .find(|r| !parser.definitions.contains_key(*r));
+
+    if let Some(missing) = missing {
+        return Err(Error::UnresolvedRef(missing.into()));
+    }
+
+    let schema = json!({
+        "$ref": format!("#/definitions/{}", api::escape(name)),
+        "definitions": parser.definitions,
+    });
+
+    Ok(::serde_json::from_value(schema)?)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Str(String),
+    Sym(char),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    definitions: Map<String, Value>,
+    /// Names of referenced declarations, which are checked after parsing.
+    references: Vec<String>,
+    /// Key and value types of `Record`s, which are converted after parsing.
+    records: Vec<(Value, Value)>,
+}
+
+impl Parser {
+    fn parse_declarations(&mut self) -> Result<(), Error> {
+        while let Some(token) = self.next() {
+            match token {
+                Token::Word(ref w) if w == "export" || w == "declare" => {}
+                Token::Word(ref w) if w == "interface" => self.parse_interface()?,
+                Token::Word(ref w) if w == "type" => self.parse_alias()?,
+                Token::Sym(';') => {}
+                _ => self.skip_statement(),
+            }
+        }
+
+        // Key types may be aliases declared after the record.
+        let definitions = self.definitions.clone();
+
+        for schema in self.definitions.values_mut() {
+            expand_records(schema, &self.records, &definitions)?;
+        }
+
+        Ok(())
+    }
+
+    fn parse_interface(&mut self) -> Result<(), Error> {
+        let name = self.declared_name()?;
+        let mut parents = Vec::new();
+
+        if self.eat_word("extends") {
+            loop {
+                parents.push(self.parse_primary()?);
+
+                if !self.eat(',') {
+                    break;
+                }
+            }
+        }
+
+        self.expect('{')?;
+        let body = self.parse_object()?;
+
+        let schema = if parents.is_empty() {
+            body
+        } else {
+            parents.push(body);
+            json!({ "allOf": parents })
+        };
+
+        self.definitions.insert(name, schema);
+        Ok(())
+    }
+
+    fn parse_alias(&mut self) -> Result<(), Error> {
+        let name = self.declared_name()?;
+        self.expect('=')?;
+        let schema = self.parse_type()?;
+        self.eat(';');
+        self.definitions.insert(name, schema);
+        Ok(())
+    }
+
+    fn declared_name(&mut self) -> Result<String, Error> {
+        let name = self.word()?;
+
+        if self.peek() == Some(&Token::Sym('<')) {
+            return Err(Error::Unsupported("generic types"));
+        }
+
+        Ok(name)
+    }
+
+    /// Parses members of an object type after the opening brace.
+    fn parse_object(&mut self) -> Result<Value, Error> {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let mut additional = None;
+
+        while !self.eat('}') {
+            // `readonly` is a modifier, unless it's the name of the property.
+            match self.peek_at(1) {
+                Some(Token::Sym(':')) | Some(Token::Sym('?')) | Some(Token::Sym('(')) => {}
+                _ => {
+                    self.eat_word("readonly");
+                }
+            }
+
+            // Index signatures: `[key: string]: T`.
+            if self.eat('[') {
+                self.word()?;
+                self.expect(':')?;
+                self.parse_type()?;
+                self.expect(']')?;
+                self.expect(':')?;
+                additional = Some(self.parse_type()?);
+                self.eat_separator();
+                continue;
+            }
+
+            let name = match self.next() {
+                Some(Token::Word(name)) | Some(Token::Str(name)) => name,
+                Some(Token::Number(n)) => n.to_string(),
+                _ => return Err(invalid("expected a property name")),
+            };
+
+            let optional = self.eat('?');
+
+            if self.peek() == Some(&Token::Sym('(')) {
+                return Err(Error::Unsupported("methods"));
+            }
+
+            self.expect(':')?;
+            let (schema, undefined) = self.parse_property_type()?;
+
+            if !optional && !undefined {
+                required.push(Value::from(name.as_str()));
+            }
+
+            properties.insert(name, schema);
+            self.eat_separator();
+        }
+
+        let mut schema = json!({"type": "object"});
+
+        if !properties.is_empty() {
+            schema["properties"] = Value::Object(properties);
+        }
+
+        if !required.is_empty() {
+            schema["required"] = Value::Array(required);
+        }
+
+        if let Some(additional) = additional {
+            schema["additionalProperties"] = additional;
+        }
+
+        Ok(schema)
+    }
+
+    /// Parses a type of a property, telling whether `undefined` is a variant of it.
+    fn parse_property_type(&mut self) -> Result<(Value, bool), Error> {
+        let variants = self.parse_variants()?;
+        let undefined = variants.iter().any(Option::is_none);
+        let variants = variants.into_iter().flatten().collect::<Vec<_>>();
+
+        Ok((union(variants)?, undefined))
+    }
+
+    fn parse_type(&mut self) -> Result<Value, Error> {
+        let variants = self.parse_variants()?;
+
+        if variants.iter().any(Option::is_none) {
+            return Err(Error::Unsupported("undefined"));
+        }
+
+        union(variants.into_iter().flatten().collect())
+    }
+
+    /// Parses variants of a union, where `None` stands for `undefined`.
+    fn parse_variants(&mut self) -> Result<Vec<Option<Value>>, Error> {
+        self.eat('|');
+        let mut variants = vec![self.parse_intersection()?];
+
+        while self.eat('|') {
+            variants.push(self.parse_intersection()?);
+        }
+
+        Ok(variants)
+    }
+
+    fn parse_intersection(&mut self) -> Result<Option<Value>, Error> {
+        self.eat('&');
+        let first = self.parse_postfix()?;
+
+        if self.peek() != Some(&Token::Sym('&')) {
+            return Ok(first);
+        }
+
+        let mut parts = vec![first.ok_or(Error::Unsupported("undefined"))?];
+
+        while self.eat('&') {
+            parts.push(
+                self.parse_postfix()?
+                    .ok_or(Error::Unsupported("undefined"))?,
+            );
+        }
+
+        Ok(Some(json!({ "allOf": parts })))
+    }
+
+    fn parse_postfix(&mut self) -> Result<Option<Value>, Error> {
+        if self.eat_word("undefined") || self.eat_word("void") {
+            return Ok(None);
+        }
+
+        // Values are never mutated, so `readonly T[]` is just an array.
+        let readonly = self.eat_word("readonly");
+        let mut schema = self.parse_primary()?;
+
+        // Arrays: `T[]`.
+        while self.peek() == Some(&Token::Sym('[')) && self.peek_at(1) == Some(&Token::Sym(']')) {
+            self.pos += 2;
+            schema = json!({"type": "array", "items": schema});
+        }
+
+        if readonly && schema.get("type") != Some(&json!("array")) {
+            return Err(invalid("`readonly` applies only to array types"));
+        }
+
+        Ok(Some(schema))
+    }
+
+    fn parse_primary(&mut self) -> Result<Value, Error> {
+        let token = self.next().ok_or_else(|| invalid("expected a type"))?;
+
+        let schema = match token {
+            Token::Str(value) => json!({ "const": value }),
+            Token::Number(value) => json!({ "const": number(value) }),
+            Token::Sym('-') => match self.next() {
+                Some(Token::Number(value)) => json!({ "const": number(-value) }),
+                _ => return Err(invalid("expected a number")),
+            },
+            Token::Sym('(') => {
+                let schema = self.parse_type()?;
+                self.expect(')')?;
+                schema
+            }
+            Token::Sym('{') => self.parse_object()?,
+            Token::Sym('[') => return Err(Error::Unsupported("tuple items")),
+            Token::Word(word) => self.parse_named(word)?,
+            _ => return Err(invalid("expected a type")),
+        };
+
+        Ok(schema)
+    }
+
+    fn parse_named(&mut self, name: String) -> Result<Value, Error> {
+        let schema = match name.as_str() {
+            "string" => json!({"type": "string"}),
+            "number" => json!({"type": "number"}),
+            "boolean" => json!({"type": "boolean"}),
+            "null" => json!({"type": "null"}),
+            "true" => json!({"const": true}),
+            "false" => json!({"const": false}),
+            "object" => json!({"type": "object"}),
+            "any" | "unknown" => json!({}),
+            // Dates are serialized by `toJSON()`.
+            "Date" => json!({"type": "string", "format": "date-time"}),
+            "Array" | "ReadonlyArray" => {
+                self.expect('<')?;
+                let items = self.parse_type()?;
+                self.expect('>')?;
+                json!({"type": "array", "items": items})
+            }
+            "Record" => {
+                self.expect('<')?;
+                let keys = self.parse_type()?;
+                self.expect(',')?;
+                let values = self.parse_type()?;
+                self.expect('>')?;
+                self.records.push((keys, values));
+                json!({ RECORD: self.records.len() - 1 })
+            }
+            _ => {
+                if self.peek() == Some(&Token::Sym('<')) {
+                    return Err(Error::Unsupported("generic types"));
+                }
+
+                let reference = json!({ "$ref": format!("#/definitions/{}", api::escape(&name)) });
+                self.references.push(name);
+                reference
+            }
+        };
+
+        Ok(schema)
+    }
+
+    /// Skips tokens up to `;` or a balanced `{ ... }` block.
+    fn skip_statement(&mut self) {
+        let mut depth = 0;
+
+        while let Some(token) = self.next() {
+            match token {
+                Token::Sym('{') => depth += 1,
+                Token::Sym('}') => {
+                    depth -= 1;
+
+                    if depth <= 0 {
+                        return;
+                    }
+                }
+                Token::Sym(';') if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn eat_separator(&mut self) {
+        if !self.eat(';') {
+            self.eat(',');
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w == word => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn eat(&mut self, sym: char) -> bool {
+        if self.peek() == Some(&Token::Sym(sym)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, sym: char) -> Result<(), Error> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            Err(invalid(&format!("expected `{}`", sym)))
+        }
+    }
+
+    fn word(&mut self) -> Result<String, Error> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(word),
+            _ => Err(invalid("expected an identifier")),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+}
+
+fn union(mut variants: Vec<Value>) -> Result<Value, Error> {
+    match variants.len() {
+        0 => Err(Error::Unsupported("undefined")),
+        1 => Ok(variants.remove(0)),
+        _ => Ok(json!({ "anyOf": variants })),
+    }
+}
+
+/// Marks a `Record` to be converted after parsing, holding its index.
+const RECORD: &str = "$record";
+
+/// Matches keys of `Record<number, V>`, which are numbers converted to strings.
+const NUMERIC_KEY: &str = r"^-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?$";
+
+fn expand_records(
+    schema: &mut Value,
+    records: &[(Value, Value)],
+    definitions: &Map<String, Value>,
+) -> Result<(), Error> {
+    if let Some(index) = schema.get(RECORD).and_then(Value::as_u64) {
+        let (ref keys, ref values) = records[index as usize];
+        *schema = record(keys, values.clone(), definitions)?;
+    }
+
+    match schema {
+        Value::Object(map) => map
+            .values_mut()
+            .try_for_each(|s| expand_records(s, records, definitions)),
+        Value::Array(items) => items
+            .iter_mut()
+            .try_for_each(|s| expand_records(s, records, definitions)),
+        _ => Ok(()),
+    }
+}
+
+/// Converts `Record<K, V>`, where keys are `string`, `number`, literals or unions of them.
+fn record(keys: &Value, values: Value, definitions: &Map<String, Value>) -> Result<Value, Error> {
+    let mut record_keys = RecordKeys::default();
+    record_keys.collect(keys, definitions, 0)?;
+
+    if record_keys.string {
+        return Ok(json!({"type": "object", "additionalProperties": values}));
+    }
+
+    let mut schema = json!({"type": "object"});
+
+    if !record_keys.literals.is_empty() {
+        let properties = record_keys
+            .literals
+            .iter()
+            .map(|literal| (literal.clone(), values.clone()))
+            .collect::<Map<_, _>>();
+
+        schema["properties"] = Value::Object(properties);
+        schema["required"] = json!(record_keys.literals);
+    }
+
+    if record_keys.numeric {
+        let numeric = json!({ "pattern": NUMERIC_KEY });
+
+        schema["propertyNames"] = if record_keys.literals.is_empty() {
+            numeric
+        } else {
+            json!({"anyOf": [numeric, {"enum": record_keys.literals}]})
+        };
+
+        schema["additionalProperties"] = values;
+    }
+
+    Ok(schema)
+}
+
+/// Keys of a `Record`, collected through unions and aliases.
+#[derive(Default)]
+struct RecordKeys {
+    string: bool,
+    numeric: bool,
+    literals: Vec<String>,
+}
+
+impl RecordKeys {
+    fn collect(
+        &mut self,
+        keys: &Value,
+        definitions: &Map<String, Value>,
+        depth: usize,
+    ) -> Result<(), Error> {
+        // Aliases can refer to each other, but not more times than there are of them.
+        if depth > definitions.len() {
+            return Err(Error::Unsupported("recursive $ref"));
+        }
+
+        if let Some(variants) = keys.get("anyOf").and_then(Value::as_array) {
+            for variant in variants {
+                self.collect(variant, definitions, depth)?;
+            }
+
+            return Ok(());
+        }
+
+        if let Some(reference) = keys.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/definitions/");
+            let target = definitions
+                .get(name)
+                .ok_or_else(|| Error::UnresolvedRef(name.into()))?;
+
+            return self.collect(target, definitions, depth + 1);
+        }
+
+        match keys.get("const") {
+            Some(Value::String(literal)) => self.literals.push(literal.clone()),
+            Some(Value::Number(literal)) => self.literals.push(literal.to_string()),
+            Some(_) => return Err(Error::Unsupported("Record keys")),
+            None if *keys == json!({"type": "string"}) => self.string = true,
+            None if *keys == json!({"type": "number"}) => self.numeric = true,
+            None => return Err(Error::Unsupported("Record keys")),
+        }
+
+        Ok(())
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+
+                loop {
+                    match chars.next() {
+                        Some('/') if prev == '*' => break,
+                        Some(c) => prev = c,
+                        None => return Err(invalid("unterminated comment")),
+                    }
+                }
+            }
+            '"' | '\'' | '`' => {
+                let mut value = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.extend(chars.next()),
+                        Some('$') if c == '`' && chars.peek() == Some(&'{') => {
+                            return Err(Error::Unsupported("template literal types"));
+                        }
+                        Some(q) if q == c => break,
+                        Some(c) => value.push(c),
+                        None => return Err(invalid("unterminated string")),
+                    }
+                }
+
+                tokens.push(Token::Str(value));
+            }
+            _ if c.is_ascii_digit() => {
+                let mut number = c.to_string();
+
+                while let Some(&c) = chars.peek() {
+                    let exponent = (c == 'e' || c == 'E') && !number.contains(['e', 'E']);
+                    let sign = (c == '+' || c == '-') && number.ends_with(['e', 'E']);
+
+                    if !c.is_ascii_digit() && c != '.' && c != '_' && !exponent && !sign {
+                        break;
+                    }
+
+                    if c != '_' {
+                        number.push(c);
+                    }
+
+                    chars.next();
+                }
+
+                let number = number
+                    .parse()
+                    .map_err(|_| invalid(&format!("invalid number: {}", number)))?;
+
+                tokens.push(Token::Number(number));
+            }
+            _ if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut word = c.to_string();
+
+                while let Some(&c) = chars.peek() {
+                    if !c.is_alphanumeric() && c != '_' && c != '$' {
+                        break;
+                    }
+
+                    word.push(c);
+                    chars.next();
+                }
+
+                tokens.push(Token::Word(word));
+            }
+            _ => tokens.push(Token::Sym(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidSchema(format!("invalid TypeScript: {}", reason))
+}
@@ -0,0 +1,219 @@
+use super::*;
+
+fn definitions(source: &str) -> Map<String, Value> {
+    let mut parser = Parser {
+        tokens: tokenize(source).unwrap(),
+        pos: 0,
+        definitions: Map::new(),
+        references: Vec::new(),
+        records: Vec::new(),
+    };
+
+    parser.parse_declarations().unwrap();
+    parser.definitions
+}
+
+#[test]
+fn it_should_convert_interfaces() {
+    let definitions = definitions(
+        "
+        /** A user. */
+        export interface User {
+            readonly id: number;
+            name?: string;
+            email: string | undefined,
+            'display-name': string | null;
+            [key: string]: unknown;
+        }
+    ",
+    );
+
+    assert_eq!(
+        definitions["User"],
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "number"},
+                "name": {"type": "string"},
+                "email": {"type": "string"},
+                "display-name": {"anyOf": [{"type": "string"}, {"type": "null"}]},
+            },
+            "required": ["id", "display-name"],
+            "additionalProperties": {},
+        })
+    );
+}
+
+#[test]
+fn it_should_read_readonly_as_a_property_name() {
+    let definitions = definitions(
+        "
+        interface A { readonly: string; }
+        interface B { readonly?: number; }
+        interface C { readonly readonly: boolean; }
+    ",
+    );
+
+    assert_eq!(
+        definitions["A"]["properties"]["readonly"],
+        json!({"type": "string"})
+    );
+    assert_eq!(definitions["B"].get("required"), None);
+    assert_eq!(definitions["C"]["required"], json!(["readonly"]));
+}
+
+#[test]
+fn it_should_convert_literal_types() {
+    let definitions = definitions("type Level = 'low' | 2 | -1.5 | true;");
+
+    assert_eq!(
+        definitions["Level"],
+        json!({"anyOf": [{"const": "low"}, {"const": 2}, {"const": -1.5}, {"const": true}]})
+    );
+}
+
+#[test]
+fn it_should_read_exponents_of_numbers() {
+    let definitions = definitions("type A = 1e3 | 1e-3 | 1E+3 | 2.5e2;");
+
+    assert_eq!(
+        definitions["A"],
+        json!({"anyOf": [{"const": 1000}, {"const": 0.001}, {"const": 1000}, {"const": 250}]})
+    );
+}
+
+#[test]
+fn it_should_convert_arrays_and_records() {
+    let definitions = definitions(
+        "
+        type A = string[][];
+        type B = Array<number | null>;
+        type C = Record<string, boolean>;
+        type D = Record<'x' | 'y', number>;
+        type E = readonly string[];
+        type F = ReadonlyArray<string>;
+    ",
+    );
+
+    assert_eq!(
+        definitions["A"],
+        json!({"type": "array", "items": {"type": "array", "items": {"type": "string"}}})
+    );
+
+    assert_eq!(
+        definitions["B"],
+        json!({"type": "array", "items": {"anyOf": [{"type": "number"}, {"type": "null"}]}})
+    );
+
+    assert_eq!(
+        definitions["C"],
+        json!({"type": "object", "additionalProperties": {"type": "boolean"}})
+    );
+
+    assert_eq!(
+        definitions["D"],
+        json!({
+            "type": "object",
+            "properties": {"x": {"type": "number"}, "y": {"type": "number"}},
+            "required": ["x", "y"],
+        })
+    );
+
+    // Readonly arrays are the same values.
+    let array = json!({"type": "array", "items": {"type": "string"}});
+    assert_eq!(definitions["E"], array);
+    assert_eq!(definitions["F"], array);
+}
+
+#[test]
+fn it_should_reject_readonly_non_arrays() {
+    let mut parser = Parser {
+        tokens: tokenize("type A = readonly string;").unwrap(),
+        pos: 0,
+        definitions: Map::new(),
+        references: Vec::new(),
+        records: Vec::new(),
+    };
+
+    match parser.parse_declarations() {
+        Err(Error::InvalidSchema(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_resolve_record_keys() {
+    let definitions = definitions(
+        "
+        type A = Record<Status, number>;
+        type B = Record<number, string>;
+        type C = Record<Status | number, boolean>;
+        type Status = 'on' | 'off';
+    ",
+    );
+
+    assert_eq!(
+        definitions["A"],
+        json!({
+            "type": "object",
+            "properties": {"on": {"type": "number"}, "off": {"type": "number"}},
+            "required": ["on", "off"],
+        })
+    );
+
+    assert_eq!(
+        definitions["B"],
+        json!({
+            "type": "object",
+            "propertyNames": {"pattern": NUMERIC_KEY},
+            "additionalProperties": {"type": "string"},
+        })
+    );
+
+    assert_eq!(
+        definitions["C"]["propertyNames"],
+        json!({"anyOf": [{"pattern": NUMERIC_KEY}, {"enum": ["on", "off"]}]})
+    );
+}
+
+#[test]
+fn it_should_reject_other_record_keys() {
+    let mut parser = Parser {
+        tokens: tokenize("type A = Record<boolean, string>;").unwrap(),
+        pos: 0,
+        definitions: Map::new(),
+        references: Vec::new(),
+        records: Vec::new(),
+    };
+
+    match parser.parse_declarations() {
+        Err(Error::Unsupported("Record keys")) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_convert_extends_into_all_of() {
+    let definitions = definitions("interface A { a: string } interface B extends A { b: number }");
+
+    assert_eq!(
+        definitions["B"],
+        json!({"allOf": [
+            {"$ref": "#/definitions/A"},
+            {"type": "object", "properties": {"b": {"type": "number"}}, "required": ["b"]},
+        ]})
+    );
+}
+
+#[test]
+fn it_should_skip_other_statements() {
+    let definitions = definitions(
+        "
+        import { Foo } from './foo';
+        declare function f(x: number): void;
+        export type Id = string;
+    ",
+    );
+
+    assert_eq!(definitions.keys().collect::<Vec<_>>(), ["Id"]);
+}
@@ -1,6 +1,17 @@
 use std::collections::{HashMap, HashSet};
 
-use schema::{RcMixed, RcStr, Type};
+use schema::{RcMixed, RcStr, Rc_, Type};
+
+/// Units are shared, so equal subtrees can be compared by pointers.
+pub type RcUnit = Rc_<Unit>;
+
+/// Alternative units: a value matches if it matches any of them.
+pub type Variants = Vec<RcUnit>;
+
+/// Plain collections: persistent ones (`im`) allocated several times more and were slower
+/// on the benchmark corpus, since units are cloned far less often than they're built.
+pub type Props = HashMap<RcStr, Variants>;
+pub type Names = HashSet<RcStr>;
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Unit {
@@ -10,25 +21,61 @@ pub struct Unit {
     pub minimum: Option<Point>,
     pub max_length: Option<u32>,
     pub min_length: Option<u32>,
-    pub pattern: HashSet<RcStr>,
-    pub additional_items: Option<Box<Unit>>,
-    pub items: Option<Box<Unit>>,
-    pub tuple: Vec<Unit>,
+    pub pattern: Names,
+    pub additional_items: Option<Variants>,
+    pub items: Option<Variants>,
+    pub tuple: Vec<Variants>,
     pub max_items: Option<u32>,
     pub min_items: Option<u32>,
     pub unique_items: bool,
     pub max_properties: Option<u32>,
     pub min_properties: Option<u32>,
-    pub required: HashSet<RcStr>,
-    pub additional_props: Option<Box<Unit>>,
-    pub property_names: Option<Box<Unit>>,
-    pub contains: Option<Box<Unit>>,
+    pub required: Names,
+    pub additional_props: Option<Variants>,
+    pub property_names: Option<Variants>,
+    pub contains: Option<Variants>,
     pub format: Option<RcStr>,
-    // TODO: should we use a persistent structure here?
-    pub properties: HashMap<RcStr, Unit>,
-    pub pattern_props: HashMap<RcStr, Unit>,
+    pub properties: Props,
+    pub pattern_props: Props,
     // TODO: dependencies
     pub type_: Option<Type>,
+    pub default: Option<RcMixed>,
+    pub read_only: bool,
+    pub write_only: bool,
+    pub deprecated: bool,
+}
+
+impl From<Unit> for RcUnit {
+    fn from(unit: Unit) -> RcUnit {
+        Rc_::new(unit)
+    }
+}
+
+impl Unit {
+    /// Iterates over all nested variants in no particular order.
+    pub fn nested(&self) -> impl Iterator<Item = &Variants> {
+        self.additional_items
+            .iter()
+            .chain(&self.items)
+            .chain(&self.tuple)
+            .chain(&self.additional_props)
+            .chain(&self.property_names)
+            .chain(&self.contains)
+            .chain(self.properties.values())
+            .chain(self.pattern_props.values())
+    }
+
+    pub fn nested_mut(&mut self) -> impl Iterator<Item = &mut Variants> {
+        self.additional_items
+            .iter_mut()
+            .chain(&mut self.items)
+            .chain(&mut self.tuple)
+            .chain(&mut self.additional_props)
+            .chain(&mut self.property_names)
+            .chain(&mut self.contains)
+            .chain(self.properties.values_mut())
+            .chain(self.pattern_props.values_mut())
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -52,7 +99,7 @@ impl Point {
         }
     }
 
-    #[allow(float_cmp)]
+    #[allow(clippy::float_cmp)]
     pub fn min(self, other: Point) -> Point {
         if self.value < other.value || self.value == other.value && other.inclusive {
             self
@@ -61,7 +108,7 @@ impl Point {
         }
     }
 
-    #[allow(float_cmp)]
+    #[allow(clippy::float_cmp)]
     pub fn max(self, other: Point) -> Point {
         if self.value > other.value || self.value == other.value && other.inclusive {
             self
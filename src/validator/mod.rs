@@ -0,0 +1,196 @@
+use serde_json::Value;
+
+use patterns::Patterns;
+use schema::{RcStr, Type};
+use unit::{Point, Unit, Variants};
+
+#[cfg(test)]
+mod spec;
+
+/// Checks instances against units, following the semantics of JSON Schema.
+#[derive(Default)]
+pub struct Validator {
+    patterns: Patterns,
+}
+
+impl Validator {
+    pub fn matches(&self, unit: &Unit, value: &Value) -> bool {
+        unit.const_.as_ref().is_none_or(|c| equal(value, c))
+            && unit.type_.is_none_or(|t| is_type(value, t))
+            && match value {
+                Value::Number(number) => number
+                    .as_f64()
+                    .is_some_and(|number| self.matches_number(unit, number)),
+                Value::String(string) => self.matches_string(unit, string),
+                Value::Array(items) => self.matches_array(unit, items),
+                Value::Object(props) => self.matches_object(unit, props),
+                _ => true,
+            }
+    }
+
+    fn matches_any(&self, variants: &Variants, value: &Value) -> bool {
+        variants.iter().any(|unit| self.matches(unit, value))
+    }
+
+    fn matches_number(&self, unit: &Unit, number: f64) -> bool {
+        unit.multiple_of.is_none_or(|m| is_multiple(number, m))
+            && unit.maximum.is_none_or(|max| below(number, max))
+            && unit.minimum.is_none_or(|min| above(number, min))
+    }
+
+    fn matches_string(&self, unit: &Unit, string: &str) -> bool {
+        let length = string.chars().count() as u64;
+
+        unit.max_length.is_none_or(|max| length <= u64::from(max))
+            && unit.min_length.is_none_or(|min| length >= u64::from(min))
+            && unit
+                .pattern
+                .iter()
+                .all(|p| self.patterns.is_match(p, string))
+    }
+
+    fn matches_array(&self, unit: &Unit, items: &[Value]) -> bool {
+        let length = items.len() as u64;
+
+        if unit.max_items.is_some_and(|max| length > u64::from(max))
+            || unit.min_items.is_some_and(|min| length < u64::from(min))
+        {
+            return false;
+        }
+
+        if unit.unique_items && !is_unique(items) {
+            return false;
+        }
+
+        // Without a tuple `additionalItems` is ignored.
+        let rest = if unit.tuple.is_empty() {
+            unit.items.as_ref()
+        } else {
+            unit.additional_items.as_ref()
+        };
+
+        let tuple_ok = unit
+            .tuple
+            .iter()
+            .zip(items)
+            .all(|(variants, item)| self.matches_any(variants, item));
+
+        let rest_ok = rest.is_none_or(|variants| {
+            items
+                .iter()
+                .skip(unit.tuple.len())
+                .all(|item| self.matches_any(variants, item))
+        });
+
+        let contains_ok = unit
+            .contains
+            .as_ref()
+            .is_none_or(|variants| items.iter().any(|item| self.matches_any(variants, item)));
+
+        tuple_ok && rest_ok && contains_ok
+    }
+
+    fn matches_object(&self, unit: &Unit, props: &serde_json::Map<String, Value>) -> bool {
+        let length = props.len() as u64;
+
+        if unit
+            .max_properties
+            .is_some_and(|max| length > u64::from(max))
+            || unit
+                .min_properties
+                .is_some_and(|min| length < u64::from(min))
+        {
+            return false;
+        }
+
+        if !unit.required.iter().all(|prop| props.contains_key(&**prop)) {
+            return false;
+        }
+
+        props.iter().all(|(key, value)| {
+            let mut additional = true;
+
+            if let Some(variants) = unit.properties.get(&RcStr::from(key.as_str())) {
+                additional = false;
+
+                if !self.matches_any(variants, value) {
+                    return false;
+                }
+            }
+
+            for (pattern, variants) in &unit.pattern_props {
+                if self.patterns.is_match(pattern, key) {
+                    additional = false;
+
+                    if !self.matches_any(variants, value) {
+                        return false;
+                    }
+                }
+            }
+
+            let name = Value::String(key.clone());
+
+            unit.property_names
+                .as_ref()
+                .is_none_or(|variants| self.matches_any(variants, &name))
+                && (!additional
+                    || unit
+                        .additional_props
+                        .as_ref()
+                        .is_none_or(|variants| self.matches_any(variants, value)))
+        })
+    }
+}
+
+fn is_type(value: &Value, type_: Type) -> bool {
+    match (value, type_) {
+        (Value::Null, Type::Null)
+        | (Value::Bool(_), Type::Boolean)
+        | (Value::Number(_), Type::Number)
+        | (Value::String(_), Type::String)
+        | (Value::Array(_), Type::Array)
+        | (Value::Object(_), Type::Object) => true,
+        // Numbers with a zero fractional part are integers.
+        (Value::Number(number), Type::Integer) => {
+            number.is_i64() || number.is_u64() || number.as_f64().is_some_and(|n| n.fract() == 0.)
+        }
+        _ => false,
+    }
+}
+
+/// Compares values structurally, treating `1` and `1.0` as equal.
+#[allow(clippy::float_cmp)]
+pub fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a == b || a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| equal(v, w)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn is_unique(items: &[Value]) -> bool {
+    items
+        .iter()
+        .enumerate()
+        .all(|(i, a)| items[i + 1..].iter().all(|b| !equal(a, b)))
+}
+
+fn is_multiple(number: f64, divisor: f64) -> bool {
+    let quotient = number / divisor;
+
+    // Decimal divisors aren't exact in binary, so allow for a rounding error.
+    quotient.is_finite() && (quotient - quotient.round()).abs() < 1e-9
+}
+
+fn below(number: f64, max: Point) -> bool {
+    number < max.value || max.inclusive && number <= max.value
+}
+
+fn above(number: f64, min: Point) -> bool {
+    number > min.value || min.inclusive && number >= min.value
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+use unit::RcUnit;
+
+fn matches(unit: Unit, value: Value) -> bool {
+    Validator::default().matches(&unit, &value)
+}
+
+#[test]
+fn it_should_treat_integral_floats_as_integers() {
+    let unit = Unit {
+        type_: Some(Type::Integer),
+        ..Unit::default()
+    };
+
+    assert!(matches(unit.clone(), json!(1.0)));
+    assert!(!matches(unit, json!(1.5)));
+}
+
+#[test]
+fn it_should_compare_numbers_by_value() {
+    let unit = Unit {
+        const_: Some(json!([1, {"a": 2}]).into()),
+        ..Unit::default()
+    };
+
+    assert!(matches(unit, json!([1.0, {"a": 2.0}])));
+}
+
+#[test]
+fn it_should_allow_rounding_errors_in_multiple_of() {
+    let unit = Unit {
+        multiple_of: Some(0.01),
+        ..Unit::default()
+    };
+
+    assert!(matches(unit.clone(), json!(19.99)));
+    assert!(!matches(unit, json!(19.995)));
+}
+
+#[test]
+fn it_should_check_only_additional_properties() {
+    let number: RcUnit = Unit {
+        type_: Some(Type::Number),
+        ..Unit::default()
+    }
+    .into();
+
+    let unit = Unit {
+        properties: vec![(RcStr::from("a"), vec![Unit::default().into()])]
+            .into_iter()
+            .collect(),
+        pattern_props: vec![(RcStr::from("^x-"), vec![Unit::default().into()])]
+            .into_iter()
+            .collect(),
+        additional_props: Some(vec![number]),
+        ..Unit::default()
+    };
+
+    assert!(matches(unit.clone(), json!({"a": "s", "x-b": "s", "c": 1})));
+    assert!(!matches(unit, json!({"c": "s"})));
+}
+
+#[test]
+fn it_should_reject_forbidden_properties() {
+    let unit = Unit {
+        properties: vec![(RcStr::from("a"), vec![])].into_iter().collect(),
+        ..Unit::default()
+    };
+
+    assert!(matches(unit.clone(), json!({"b": 1})));
+    assert!(!matches(unit, json!({"a": 1})));
+}
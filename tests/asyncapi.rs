@@ -0,0 +1,212 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+use checkco::{Compatibility, Options, Verdict};
+use serde_json::Value;
+
+fn check(derived: Value, base: Value, compatibility: Compatibility) -> Vec<(String, &'static str)> {
+    match checkco::check_asyncapi(&derived, &base, compatibility, &Options::default()).unwrap() {
+        Verdict::Success => Vec::new(),
+        Verdict::Failure(mismatches) => mismatches
+            .into_iter()
+            .map(|m| (m.pointer, m.keyword))
+            .collect(),
+    }
+}
+
+fn v2(payload: Value) -> Value {
+    json!({
+        "asyncapi": "2.6.0",
+        "info": {"title": "orders", "version": "1"},
+        "channels": {
+            "orders": {
+                "subscribe": {
+                    "message": {"$ref": "#/components/messages/OrderPlaced"},
+                },
+            },
+        },
+        "components": {
+            "messages": {
+                "OrderPlaced": {
+                    "messageId": "orderPlaced",
+                    "headers": {"type": "object", "properties": {"trace": {"type": "string"}}},
+                    "payload": {"$ref": "#/components/schemas/Order"},
+                },
+            },
+            "schemas": {"Order": payload},
+        },
+    })
+}
+
+fn v3(messages: Value) -> Value {
+    json!({
+        "asyncapi": "3.0.0",
+        "info": {"title": "orders", "version": "1"},
+        "channels": {
+            "orders": {"address": "orders.v1", "messages": messages},
+        },
+    })
+}
+
+#[test]
+fn it_should_check_backward_compatibility() {
+    let base = v2(json!({"type": "object", "properties": {"qty": {"type": "integer"}}}));
+    let derived = v2(json!({"type": "object", "properties": {"qty": {"type": "number"}}}));
+
+    assert_eq!(
+        check(derived.clone(), base.clone(), Compatibility::Backward),
+        vec![]
+    );
+    assert_eq!(
+        check(base, derived, Compatibility::Backward),
+        vec![(
+            "/channels/orders/subscribe/message/payload/properties/qty".into(),
+            "type"
+        )]
+    );
+}
+
+#[test]
+fn it_should_check_forward_compatibility() {
+    let base = v2(json!({"type": "object", "properties": {"qty": {"type": "integer"}}}));
+    let derived = v2(json!({"type": "object", "properties": {"qty": {"type": "number"}}}));
+
+    assert_eq!(
+        check(base.clone(), derived.clone(), Compatibility::Forward),
+        vec![]
+    );
+    assert_eq!(
+        check(derived.clone(), base.clone(), Compatibility::Forward).len(),
+        1
+    );
+    assert_eq!(check(derived, base, Compatibility::Full).len(), 1);
+}
+
+#[test]
+fn it_should_pair_channels_by_address() {
+    let base = v3(json!({"placed": {"payload": {"type": "string"}}}));
+    let mut derived = base.clone();
+    derived["channels"] = json!({
+        "renamed": {"address": "orders.v1", "messages": {"placed": {"payload": {"type": "string"}}}},
+    });
+
+    assert_eq!(check(derived, base, Compatibility::Full), vec![]);
+}
+
+#[test]
+fn it_should_report_removed_and_added_messages() {
+    let base = v3(json!({"placed": {"payload": {}}, "cancelled": {"payload": {}}}));
+    let derived = v3(json!({"placed": {"payload": {}}, "shipped": {"payload": {}}}));
+
+    assert_eq!(
+        check(derived.clone(), base.clone(), Compatibility::Backward),
+        vec![("/channels/orders/messages/cancelled".into(), "message")]
+    );
+
+    assert_eq!(
+        check(derived, base, Compatibility::Forward),
+        vec![("/channels/orders/messages/shipped".into(), "message")]
+    );
+}
+
+#[test]
+fn it_should_check_headers() {
+    let base = v3(json!({"placed": {"headers": {"properties": {"trace": {"type": "string"}}}}}));
+    let derived =
+        v3(json!({"placed": {"headers": {"properties": {"trace": {"type": "integer"}}}}}));
+
+    assert_eq!(
+        check(derived, base, Compatibility::Backward),
+        vec![(
+            "/channels/orders/messages/placed/headers/properties/trace".into(),
+            "type"
+        )]
+    );
+}
+
+#[test]
+fn it_should_pair_unnamed_messages_by_schemas() {
+    let unnamed = |messages: Value| {
+        json!({
+            "asyncapi": "2.6.0",
+            "info": {"title": "orders", "version": "1"},
+            "channels": {
+                "orders": {"subscribe": {"message": {"oneOf": messages}}},
+            },
+        })
+    };
+
+    let placed = json!({"payload": {"type": "object", "required": ["qty"]}});
+    let cancelled = json!({"payload": {"type": "object", "required": ["reason"]}});
+    let shipped = json!({"payload": {"type": "object", "required": ["carrier"]}});
+
+    // Reordering unnamed messages keeps them paired.
+    let base = unnamed(json!([placed, cancelled]));
+    let derived = unnamed(json!([cancelled, placed]));
+    assert_eq!(check(derived, base.clone(), Compatibility::Full), vec![]);
+
+    // Changed ones aren't paired with others by their position.
+    let derived = unnamed(json!([placed, shipped]));
+    let unpaired = (
+        "/channels/orders/subscribe/message/oneOf/1".into(),
+        "message",
+    );
+
+    assert_eq!(
+        check(derived.clone(), base.clone(), Compatibility::Backward),
+        vec![unpaired.clone()]
+    );
+    assert_eq!(check(derived, base, Compatibility::Forward), vec![unpaired]);
+}
+
+#[test]
+fn it_should_pair_messages_of_operations_separately() {
+    let document = |received: Value, sent: Value| {
+        json!({
+            "asyncapi": "2.6.0",
+            "info": {"title": "orders", "version": "1"},
+            "channels": {
+                "orders": {
+                    "publish": {"message": {"messageId": "order", "payload": received}},
+                    "subscribe": {"message": {"messageId": "order", "payload": sent}},
+                },
+            },
+        })
+    };
+
+    let base = document(json!({"type": "string"}), json!({"type": "integer"}));
+    let derived = document(json!({"type": "string"}), json!({"type": "number"}));
+
+    // Sent messages are checked even though received ones have the same identifier.
+    assert_eq!(
+        check(base, derived, Compatibility::Backward),
+        vec![("/channels/orders/subscribe/message/payload".into(), "type")]
+    );
+}
+
+#[test]
+fn it_should_check_avro_payloads() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/asyncapi-avro.json"
+    );
+    let base = serde_json::from_str::<Value>(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+    // A wider type of `age`.
+    let mut derived = base.clone();
+    derived["components"]["messages"]["UserSignedUp"]["payload"]["fields"][1]["type"] =
+        json!(["null", "long"]);
+
+    assert_eq!(
+        check(derived.clone(), base.clone(), Compatibility::Backward),
+        vec![]
+    );
+    assert_eq!(
+        check(derived, base, Compatibility::Forward),
+        vec![(
+            "/channels/users/subscribe/message/payload/properties/age".into(),
+            "type"
+        )]
+    );
+}
@@ -0,0 +1,63 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use common::{is_compatible, schema};
+use serde_json::Value;
+
+fn user() -> Value {
+    json!({
+        "type": "record",
+        "name": "User",
+        "namespace": "com.example",
+        "fields": [
+            {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+            {"name": "age", "type": ["null", "int"], "default": null},
+            {"name": "tags", "type": {"type": "array", "items": "string"}},
+            {"name": "attrs", "type": {"type": "map", "values": "long"}},
+            {"name": "role", "type": {"type": "enum", "name": "Role", "symbols": ["ADMIN", "USER"]}},
+            {"name": "hash", "type": {"type": "fixed", "name": "Hash", "size": 16}},
+        ],
+    })
+}
+
+#[test]
+fn it_should_verify_migration_to_json_schema() {
+    let avro = checkco::from_avro(&user()).unwrap();
+
+    let migrated = schema(json!({
+        "type": "object",
+        "properties": {
+            "id": {"type": "string"},
+            "age": {"type": ["integer", "null"]},
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "attrs": {"type": "object", "additionalProperties": {"type": "integer"}},
+            "role": {"type": "string"},
+            "hash": {"type": "string"},
+        },
+        "required": ["id", "tags"],
+    }));
+
+    assert!(is_compatible(avro, migrated.clone()));
+
+    let avro = checkco::from_avro(&user()).unwrap();
+    assert!(!is_compatible(migrated, avro));
+}
+
+#[test]
+fn it_should_detect_narrowed_types() {
+    let avro = checkco::from_avro(&json!("long")).unwrap();
+    let migrated = schema(json!({"type": "integer", "maximum": 2147483647}));
+
+    assert!(!is_compatible(avro, migrated));
+}
+
+#[test]
+fn it_should_keep_numbers_of_logical_types() {
+    let avro = json!({"type": "long", "logicalType": "timestamp-millis"});
+    let migrated = schema(json!({"type": "integer"}));
+
+    assert!(is_compatible(checkco::from_avro(&avro).unwrap(), migrated));
+}
@@ -0,0 +1,343 @@
+#[macro_use]
+extern crate serde_json;
+
+use serde_json::Value;
+use std::process::Command;
+
+fn checkco(derived: &str, base: &str) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_checkco"))
+        .arg(format!("tests/fixtures/{}", derived))
+        .arg(format!("tests/fixtures/{}", base))
+        .output()
+        .unwrap()
+        .status
+        .code()
+}
+
+#[test]
+fn it_should_exit_with_zero_if_compatible() {
+    assert_eq!(checkco("base.json", "base.json"), Some(0));
+}
+
+#[test]
+fn it_should_exit_with_one_if_incompatible() {
+    assert_eq!(checkco("derived.json", "base.json"), Some(1));
+}
+
+#[test]
+fn it_should_exit_with_two_if_input_is_invalid() {
+    assert_eq!(checkco("invalid.json", "base.json"), Some(2));
+    assert_eq!(checkco("missing.json", "base.json"), Some(2));
+}
+
+#[test]
+fn it_should_exit_with_three_if_unsupported() {
+    assert_eq!(checkco("one-of.json", "base.json"), Some(3));
+}
+
+fn render(format: &str, derived: &str, base: &str) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+        .args(["--format", format])
+        .arg(format!("tests/fixtures/{}", derived))
+        .arg(format!("tests/fixtures/{}", base))
+        .output()
+        .unwrap();
+
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn it_should_render_json() {
+    assert_eq!(
+        render("json", "derived.json", "base.json"),
+        json!({
+            "compatible": false,
+            "incompatibilities": [
+                {"pointer": "/properties/a", "keyword": "maximum"},
+                {"pointer": "/properties/b", "keyword": "maxLength"},
+            ],
+        })
+    );
+
+    assert_eq!(
+        render("json", "base.json", "base.json"),
+        json!({"compatible": true, "incompatibilities": []})
+    );
+}
+
+#[test]
+fn it_should_render_sarif() {
+    let sarif = render("sarif", "derived.json", "base.json");
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+
+    let lines = results
+        .iter()
+        .map(|r| &r["locations"][0]["physicalLocation"]["region"]["startLine"])
+        .collect::<Vec<_>>();
+
+    // `maximum` of `a` and `b` itself, which has no `maxLength`.
+    assert_eq!(lines, [&json!(7), &json!(9)]);
+    assert_eq!(results[0]["ruleId"], json!("maximum"));
+}
+
+#[test]
+fn it_should_render_sarif_without_lines_of_converted_schemas() {
+    let sarif = render("sarif", "user.avsc", "derived.json");
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+
+    assert!(!results.is_empty());
+
+    for result in results {
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            json!("tests/fixtures/user.avsc")
+        );
+        assert_eq!(location.get("region"), None);
+    }
+}
+
+#[test]
+fn it_should_check_openapi_documents() {
+    let check = |derived: &str, base: &str| {
+        Command::new(env!("CARGO_BIN_EXE_checkco"))
+            .arg("--openapi")
+            .arg(format!("tests/fixtures/{}", derived))
+            .arg(format!("tests/fixtures/{}", base))
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+
+    assert_eq!(check("openapi-derived.json", "openapi-base.json"), Some(0));
+    assert_eq!(check("openapi-base.json", "openapi-derived.json"), Some(1));
+    assert_eq!(check("base.json", "openapi-base.json"), Some(2));
+}
+
+#[test]
+fn it_should_check_asyncapi_documents() {
+    let check = |derived: &str, base: &str, compatibility: &str| {
+        Command::new(env!("CARGO_BIN_EXE_checkco"))
+            .args(["--asyncapi", "--compatibility", compatibility])
+            .arg(format!("tests/fixtures/{}", derived))
+            .arg(format!("tests/fixtures/{}", base))
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+
+    assert_eq!(
+        check("asyncapi-derived.json", "asyncapi-base.json", "backward"),
+        Some(0)
+    );
+    assert_eq!(
+        check("asyncapi-derived.json", "asyncapi-base.json", "forward"),
+        Some(1)
+    );
+}
+
+#[test]
+fn it_should_reject_modes_of_documents() {
+    for flag in &["--openapi", "--asyncapi"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+            .args([flag, "--mode", "request"])
+            .arg("tests/fixtures/openapi-derived.json")
+            .arg("tests/fixtures/openapi-base.json")
+            .output()
+            .unwrap();
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(stderr.contains("cannot be used with '--mode <mode>'"));
+    }
+}
+
+#[test]
+fn it_should_convert_avro_schemas() {
+    assert_eq!(checkco("user.avsc", "user.json"), Some(0));
+    assert_eq!(checkco("base.json", "user.avsc"), Some(1));
+}
+
+#[test]
+fn it_should_convert_proto_messages() {
+    let check = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_checkco"))
+            .args(args)
+            .args(["tests/fixtures/user.proto", "tests/fixtures/user.json"])
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+
+    // `name` isn't required in proto3.
+    assert_eq!(check(&["--message", "User"]), Some(1));
+    assert_eq!(check(&[]), Some(2));
+}
+
+#[test]
+fn it_should_convert_typescript_declarations() {
+    let check = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_checkco"))
+            .args(args)
+            .args(["tests/fixtures/user.json", "tests/fixtures/user.d.ts"])
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+
+    assert_eq!(check(&["--type", "User"]), Some(0));
+    assert_eq!(check(&["--type", "Missing"]), Some(2));
+    assert_eq!(check(&[]), Some(2));
+}
+
+#[test]
+fn it_should_normalize_schemas() {
+    let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+        .args(["normalize", "tests/fixtures/user.json"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let normalized: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(normalized["required"], json!(["name"]));
+}
+
+#[test]
+fn it_should_intersect_and_unite_schemas() {
+    let run = |command: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+            .args([
+                command,
+                "tests/fixtures/base.json",
+                "tests/fixtures/user.json",
+            ])
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(0));
+        serde_json::from_slice::<Value>(&output.stdout).unwrap()
+    };
+
+    assert_eq!(run("intersect")["required"], json!(["name"]));
+    assert_eq!(run("union")["anyOf"].as_array().map(Vec::len), Some(2));
+    assert_eq!(run("join")["type"], json!("object"));
+}
+
+#[test]
+fn it_should_intersect_many_schemas() {
+    let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+        .args([
+            "intersect",
+            "tests/fixtures/consumer-a.json",
+            "tests/fixtures/consumer-b.json",
+            "tests/fixtures/consumer-c.json",
+        ])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    // The first two schemas disagree on `a`, so it must be absent.
+    let output = serde_json::from_slice::<Value>(&output.stdout).unwrap();
+    assert_eq!(output["properties"]["a"], json!(false));
+    assert_eq!(output["required"], json!(["b"]));
+}
+
+#[test]
+fn it_should_describe_differences() {
+    let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+        .args([
+            "difference",
+            "tests/fixtures/derived.json",
+            "tests/fixtures/base.json",
+        ])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = serde_json::from_slice::<Value>(&output.stdout).unwrap();
+    assert_eq!(output["examples"][1], json!({"a": 11}));
+    assert_eq!(output["schema"]["anyOf"].as_array().map(Vec::len), Some(2));
+}
+
+#[test]
+fn it_should_check_inferred_schemas() {
+    let infer = |base: &str| {
+        Command::new(env!("CARGO_BIN_EXE_checkco"))
+            .args(["infer", "tests/fixtures/samples.jsonl", "--base"])
+            .arg(format!("tests/fixtures/{}", base))
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+
+    assert_eq!(infer("base.json"), Some(0));
+    assert_eq!(infer("user.json"), Some(1));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+        .args(["infer", "tests/fixtures/samples.jsonl", "--max-enum", "0"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let inferred = serde_json::from_slice::<Value>(&output.stdout).unwrap();
+    assert_eq!(inferred["required"], json!(["a"]));
+    assert_eq!(inferred["properties"]["a"]["maximum"], json!(7));
+}
+
+fn infer(format: &str, args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_checkco"))
+        .args(["infer", "tests/fixtures/samples.jsonl", "--format", format])
+        .args(["--base", "tests/fixtures/user.json"])
+        .args(args)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn it_should_render_checks_of_inferred_schemas() {
+    let output = infer("json", &[]);
+    assert_eq!(output["compatible"], json!(false));
+    assert_eq!(
+        output["incompatibilities"][0],
+        json!({"pointer": "", "keyword": "required"})
+    );
+
+    // Without a file of the inferred schema, results have no physical location.
+    let sarif = infer("sarif", &[]);
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+
+    assert!(!results.is_empty());
+
+    for result in results {
+        assert_eq!(result["locations"][0].get("physicalLocation"), None);
+    }
+
+    let path = std::env::temp_dir().join(format!("checkco-inferred-{}.json", std::process::id()));
+    let sarif = infer("sarif", &["--output", path.to_str().unwrap()]);
+    let inferred = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let location = &sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"];
+    let line = location["region"]["startLine"].as_u64().unwrap() as usize;
+
+    assert_eq!(location["artifactLocation"]["uri"], json!(path.to_str()));
+    assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], json!("required"));
+    assert!(inferred
+        .lines()
+        .nth(line - 1)
+        .unwrap()
+        .contains("\"required\""));
+}
@@ -0,0 +1,172 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use checkco::Relation;
+use common::schema;
+use serde_json::Value;
+
+fn intersect(a: Value, b: Value) -> Value {
+    checkco::intersect(schema(a), schema(b)).unwrap()
+}
+
+fn union(a: Value, b: Value) -> Value {
+    checkco::union(schema(a), schema(b)).unwrap()
+}
+
+fn join(schemas: Vec<Value>) -> Value {
+    checkco::join(schemas.into_iter().map(schema).collect()).unwrap()
+}
+
+#[test]
+fn it_should_intersect_consumers() {
+    let a = json!({
+        "type": "object",
+        "properties": {"id": {"type": "integer", "minimum": 0}},
+        "required": ["id"],
+    });
+
+    let b = json!({
+        "type": "object",
+        "properties": {"id": {"type": "number", "maximum": 100}, "name": {"type": "string"}},
+    });
+
+    let both = intersect(a.clone(), b.clone());
+
+    assert_eq!(
+        both,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer", "minimum": 0, "maximum": 100},
+                "name": {"type": "string"},
+            },
+            "required": ["id"],
+        })
+    );
+
+    let both = || schema(both.clone());
+    assert_eq!(
+        checkco::compare(both(), schema(a)).unwrap(),
+        Relation::Subtype
+    );
+    assert_eq!(
+        checkco::compare(both(), schema(b)).unwrap(),
+        Relation::Subtype
+    );
+}
+
+#[test]
+fn it_should_intersect_disjunctions() {
+    let a = json!({"type": ["string", "integer", "null"]});
+    let b =
+        json!({"anyOf": [{"type": "number", "multipleOf": 2}, {"type": "string", "maxLength": 3}]});
+
+    let both = intersect(a, b);
+    let expected = json!({"anyOf": [
+        {"type": "integer", "multipleOf": 2},
+        {"type": "string", "maxLength": 3},
+    ]});
+
+    assert!(checkco::equivalent(schema(both), schema(expected)).unwrap());
+}
+
+#[test]
+fn it_should_produce_never_for_disjoint_schemas() {
+    let both = intersect(json!({"type": "string"}), json!({"type": "null"}));
+    assert_eq!(both["not"], json!({}));
+}
+
+#[test]
+fn it_should_intersect_after_never() {
+    let schemas = vec![
+        json!({"type": "string"}),
+        json!({"type": "null"}),
+        json!({"type": "integer"}),
+    ];
+
+    let all = checkco::intersect_all(schemas.into_iter().map(schema).collect()).unwrap();
+    assert_eq!(all["not"], json!({}));
+}
+
+#[test]
+fn it_should_unite_schemas() {
+    let either = union(
+        json!({"type": "integer", "maximum": 10}),
+        json!({"anyOf": [{"type": "integer", "maximum": 5}, {"type": "string"}]}),
+    );
+
+    assert_eq!(
+        either,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "anyOf": [{"type": "integer", "maximum": 10}, {"type": "string"}],
+        })
+    );
+}
+
+#[test]
+fn it_should_join_provider_versions() {
+    let v1 = json!({
+        "type": "object",
+        "properties": {
+            "id": {"type": "integer", "minimum": 1},
+            "name": {"type": "string", "maxLength": 10},
+        },
+        "required": ["id", "name"],
+    });
+
+    let v2 = json!({
+        "type": "object",
+        "properties": {
+            "id": {"type": "integer", "minimum": 0, "maximum": 100},
+            "name": {"type": "string", "maxLength": 20},
+        },
+        "required": ["id"],
+    });
+
+    let joined = join(vec![v1.clone(), v2.clone()]);
+
+    assert_eq!(
+        joined,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {
+                "id": {"anyOf": [
+                    {"type": "integer", "minimum": 0, "maximum": 100},
+                    {"type": "integer", "minimum": 1},
+                ]},
+                "name": {"type": "string", "maxLength": 20},
+            },
+            "required": ["id"],
+        })
+    );
+
+    for version in [v1, v2] {
+        assert_eq!(
+            checkco::compare(schema(version), schema(joined.clone())).unwrap(),
+            Relation::Subtype
+        );
+    }
+}
+
+#[test]
+fn it_should_join_units_of_each_type() {
+    let joined = join(vec![
+        json!({"type": "integer", "maximum": 10}),
+        json!({"anyOf": [{"type": "number", "maximum": 20}, {"type": "string"}]}),
+        json!({"type": "string", "minLength": 1}),
+    ]);
+
+    assert_eq!(
+        joined,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "anyOf": [{"type": "number", "maximum": 20}, {"type": "string"}],
+        })
+    );
+}
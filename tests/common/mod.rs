@@ -0,0 +1,15 @@
+//! Helpers shared by integration tests.
+
+// Every test crate compiles its own copy, using only some of the helpers.
+#![allow(dead_code)]
+
+use checkco::{Schema, Verdict};
+use serde_json::{self, Value};
+
+pub fn schema(value: Value) -> Schema {
+    serde_json::from_value(value).unwrap()
+}
+
+pub fn is_compatible(derived: Schema, base: Schema) -> bool {
+    matches!(checkco::check(derived, base).unwrap(), Verdict::Success)
+}
@@ -0,0 +1,103 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use checkco::Relation;
+use common::schema;
+use serde_json::Value;
+
+fn compare(derived: Value, base: Value) -> Relation {
+    checkco::compare(schema(derived), schema(base)).unwrap()
+}
+
+#[test]
+fn it_should_detect_equal_schemas() {
+    let a = json!({"type": "integer", "minimum": 0, "maximum": 10});
+    let b = json!({"allOf": [{"type": "integer"}, {"minimum": 0, "maximum": 10}]});
+
+    assert_eq!(compare(a.clone(), b.clone()), Relation::Equal);
+    assert!(checkco::equivalent(schema(a), schema(b)).unwrap());
+}
+
+#[test]
+fn it_should_detect_subtype() {
+    let a = json!({"type": "integer", "maximum": 5});
+    let b = json!({"type": "number"});
+
+    assert_eq!(compare(a.clone(), b.clone()), Relation::Subtype);
+    assert_eq!(compare(b, a), Relation::Supertype);
+}
+
+#[test]
+fn it_should_detect_incomparable_schemas() {
+    let a = json!({"type": "integer"});
+    let b = json!({"type": "string"});
+
+    assert_eq!(compare(a.clone(), b.clone()), Relation::Incomparable);
+    assert!(!checkco::equivalent(schema(a), schema(b)).unwrap());
+}
+
+#[test]
+fn it_should_compare_boolean_schemas() {
+    assert_eq!(compare(json!(true), json!({})), Relation::Equal);
+    assert_eq!(
+        compare(json!(false), json!({"type": "string"})),
+        Relation::Subtype
+    );
+    assert_eq!(
+        compare(json!({"properties": {"a": false}}), json!({})),
+        Relation::Subtype
+    );
+}
+
+#[test]
+fn it_should_check_disjunctions_inside_properties() {
+    let a = json!({"properties": {"a": {"type": "integer"}, "b": {"type": "string"}}});
+    let b = json!({"properties": {
+        "a": {"type": ["integer", "null"]},
+        "b": {"anyOf": [{"type": "string"}, {"type": "boolean"}]},
+    }});
+
+    assert_eq!(compare(a, b), Relation::Subtype);
+}
+
+#[test]
+fn it_should_split_ranges_across_union() {
+    let a = json!({"type": "integer", "minimum": 0, "maximum": 10});
+    let b = json!({"anyOf": [{"maximum": 5}, {"minimum": 5}]});
+
+    assert_eq!(compare(a, b), Relation::Subtype);
+}
+
+#[test]
+fn it_should_split_types_across_union() {
+    let a = json!({"properties": {"id": {"type": "integer", "minimum": 0}}});
+    let b = json!({"anyOf": [
+        {"type": "object", "properties": {"id": {"type": "number"}}},
+        {"type": ["null", "boolean", "array", "number", "string"]},
+    ]});
+
+    assert_eq!(compare(a, b), Relation::Subtype);
+}
+
+#[test]
+fn it_should_split_required_properties_across_union() {
+    let a = json!({"type": "object", "properties": {"id": {"type": "integer"}}});
+    let b = json!({"anyOf": [
+        {"required": ["id"], "properties": {"id": {"type": "number"}}},
+        // Contradicting subschema forbids the property.
+        {"properties": {"id": {"allOf": [{"type": "string"}, {"type": "integer"}]}}},
+    ]});
+
+    assert_eq!(compare(a, b), Relation::Subtype);
+}
+
+#[test]
+fn it_should_not_cover_gaps_in_union() {
+    let a = json!({"type": "number", "minimum": 0, "maximum": 10});
+    let b = json!({"anyOf": [{"maximum": 4}, {"minimum": 5}]});
+
+    assert_eq!(compare(a, b), Relation::Incomparable);
+}
@@ -0,0 +1,104 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use checkco::Difference;
+use common::schema;
+use serde_json::Value;
+
+fn difference(derived: Value, base: Value) -> Difference {
+    checkco::difference(schema(derived), schema(base)).unwrap()
+}
+
+#[test]
+fn it_should_describe_widened_properties() {
+    let base = json!({
+        "type": "object",
+        "properties": {"age": {"type": "integer", "minimum": 0, "maximum": 150}},
+        "required": ["age"],
+    });
+
+    let derived = json!({
+        "type": "object",
+        "properties": {"age": {"type": "integer", "minimum": 0, "maximum": 200}},
+        "required": ["age"],
+    });
+
+    let diff = difference(derived.clone(), base.clone());
+
+    assert_eq!(
+        diff.schema,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {"age": {"type": "integer", "exclusiveMinimum": 150, "maximum": 200}},
+            "required": ["age"],
+        })
+    );
+    assert_eq!(diff.examples, vec![json!({"age": 151})]);
+
+    for example in &diff.examples {
+        assert!(checkco::validate(schema(derived.clone()), example).unwrap());
+        assert!(!checkco::validate(schema(base.clone()), example).unwrap());
+    }
+}
+
+#[test]
+fn it_should_describe_dropped_requirements() {
+    let base = json!({
+        "type": "object",
+        "properties": {"id": {"type": "string"}},
+        "required": ["id"],
+    });
+
+    let derived = json!({
+        "type": "object",
+        "properties": {"id": {"type": "string"}},
+    });
+
+    let diff = difference(derived, base);
+
+    assert_eq!(
+        diff.schema,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {"id": false},
+        })
+    );
+    assert_eq!(diff.examples, vec![json!({})]);
+    assert!(diff.exact);
+}
+
+#[test]
+fn it_should_be_empty_for_compatible_changes() {
+    let base = json!({"type": ["string", "null"]});
+    let derived = json!({"type": "string", "maxLength": 10});
+
+    let diff = difference(derived, base);
+
+    assert_eq!(
+        diff.schema,
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "not": {}})
+    );
+    assert!(diff.examples.is_empty());
+    assert!(diff.exact);
+}
+
+#[test]
+fn it_should_report_inexact_differences() {
+    let base = json!({"type": "string", "pattern": "^[a-z]+$"});
+    let derived = json!({"type": ["string", "null"]});
+
+    let diff = difference(derived, base);
+
+    // Strings not matching the pattern have no unit, so only `null` is described.
+    assert_eq!(
+        diff.schema,
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "type": "null"})
+    );
+    assert_eq!(diff.examples, vec![Value::Null]);
+    assert!(!diff.exact);
+}
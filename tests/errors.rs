@@ -0,0 +1,144 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+use checkco::{Error, Schema, Verdict};
+use serde_json::Value;
+
+fn check(derived: Value, base: Value) -> Result<Verdict, Error> {
+    let derived: Schema = serde_json::from_value(derived).unwrap();
+    let base: Schema = serde_json::from_value(base).unwrap();
+    checkco::check(derived, base)
+}
+
+#[test]
+fn it_should_parse_schemas() {
+    assert!("{\"type\": \"string\"}".parse::<Schema>().is_ok());
+
+    match "{\"type\": 42}".parse::<Schema>() {
+        Err(Error::InvalidSchema(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_resolve_local_refs() {
+    let derived = json!({
+        "definitions": {"id": {"type": "integer", "minimum": 1}},
+        "properties": {"id": {"$ref": "#/definitions/id"}},
+    });
+
+    let base = json!({"properties": {"id": {"type": "number"}}});
+
+    match check(derived, base) {
+        Ok(Verdict::Success) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_report_unresolved_refs() {
+    let derived = json!({"$ref": "#/definitions/missing"});
+
+    match check(derived, json!({})) {
+        Err(Error::UnresolvedRef(ref reference)) if reference == "#/definitions/missing" => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_report_recursive_refs() {
+    let derived = json!({"properties": {"child": {"$ref": "#"}}});
+
+    match check(derived, json!({})) {
+        Err(Error::Unsupported("recursive $ref")) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_report_invalid_regexes() {
+    let derived = json!({"pattern": "(unclosed"});
+
+    match check(derived, json!({})) {
+        Err(Error::InvalidRegex(ref pattern)) if pattern == "(unclosed" => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_report_invalid_schemas() {
+    match check(json!({"multipleOf": 0}), json!({})) {
+        Err(Error::InvalidSchema(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    match check(json!({}), json!({"enum": []})) {
+        Err(Error::InvalidSchema(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_report_unsupported_features() {
+    match check(json!({"not": {"type": "string"}}), json!({})) {
+        Err(Error::Unsupported("not")) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    match check(json!({"if": {}, "then": {}}), json!({})) {
+        Err(Error::Unsupported("if")) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_report_combinatorial_blow_up() {
+    let branches = (0..20)
+        .map(|i| json!({"anyOf": [{"minimum": i}, {"maximum": i}, {"multipleOf": i + 1}]}))
+        .collect::<Vec<_>>();
+
+    match check(json!({ "allOf": branches }), json!({})) {
+        Err(Error::TooManyUnits(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_respect_unit_budget() {
+    let derived: Schema = serde_json::from_value(json!({
+        "type": ["integer", "string"],
+        "enum": [1, 2],
+    }))
+    .unwrap();
+
+    let options = checkco::Options {
+        max_units: 3,
+        ..checkco::Options::default()
+    };
+
+    match checkco::check_with(derived, "{}".parse().unwrap(), &options) {
+        Err(Error::TooManyUnits(3)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_charge_rejected_combinations() {
+    // Only 3 of 3^20 combinations agree on the constant.
+    let branches = (0..20)
+        .map(|_| json!({"anyOf": [{"const": 1}, {"const": 2}, {"const": 3}]}))
+        .collect::<Vec<_>>();
+
+    let derived: Schema = serde_json::from_value(json!({ "allOf": branches })).unwrap();
+
+    let options = checkco::Options {
+        max_units: 100,
+        ..checkco::Options::default()
+    };
+
+    match checkco::check_with(derived, "{}".parse().unwrap(), &options) {
+        Err(Error::TooManyUnits(100)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
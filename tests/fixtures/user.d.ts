@@ -0,0 +1,4 @@
+export interface User {
+  name: string;
+  age?: number | null;
+}
@@ -0,0 +1,80 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use checkco::Verdict;
+use common::schema;
+use serde_json::Value;
+
+fn samples() -> Vec<Value> {
+    vec![
+        json!({"id": 1, "status": "active", "tags": ["a"]}),
+        json!({"id": 20, "status": "closed", "tags": []}),
+        json!({"id": 7, "status": "active", "note": null}),
+    ]
+}
+
+#[test]
+fn it_should_infer_schemas_of_samples() {
+    let inferred = checkco::infer(&samples(), 2);
+
+    assert_eq!(
+        inferred,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer", "minimum": 1, "maximum": 20},
+                "note": {"type": "null"},
+                "status": {"anyOf": [
+                    {"type": "string", "const": "active"},
+                    {"type": "string", "const": "closed"},
+                ]},
+                "tags": {
+                    "type": "array",
+                    "items": {"type": "string", "const": "a"},
+                    "minItems": 0,
+                    "maxItems": 1,
+                },
+            },
+            "required": ["id", "status"],
+        })
+    );
+
+    for sample in samples() {
+        assert!(checkco::validate(schema(inferred.clone()), &sample).unwrap());
+    }
+}
+
+#[test]
+fn it_should_check_inferred_schemas() {
+    let inferred = || schema(checkco::infer(&samples(), 8));
+
+    let base = json!({
+        "type": "object",
+        "properties": {
+            "id": {"type": "integer", "minimum": 0},
+            "status": {"enum": ["active", "closed", "pending"]},
+            "tags": {"type": "array", "items": {"type": "string"}},
+        },
+        "required": ["id"],
+    });
+
+    let verdict = checkco::check(inferred(), schema(base)).unwrap();
+    assert!(matches!(verdict, Verdict::Success));
+
+    let base = json!({
+        "type": "object",
+        "properties": {"id": {"type": "integer", "maximum": 10}},
+    });
+
+    let verdict = checkco::check(inferred(), schema(base)).unwrap();
+    assert!(matches!(verdict, Verdict::Failure(_)));
+}
+
+#[test]
+fn it_should_infer_nothing_without_samples() {
+    assert_eq!(checkco::infer(&[], 8)["not"], json!({}));
+}
@@ -0,0 +1,73 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use checkco::Options;
+use common::schema;
+use serde_json::Value;
+
+fn normalize(value: Value) -> Value {
+    checkco::normalize(schema(value), &Options::default()).unwrap()
+}
+
+#[test]
+fn it_should_inline_refs_and_merge_all_of() {
+    let normalized = normalize(json!({
+        "definitions": {"positive": {"minimum": 0}},
+        "allOf": [{"$ref": "#/definitions/positive"}, {"type": "integer", "maximum": 10}],
+    }));
+
+    assert_eq!(
+        normalized,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "integer",
+            "minimum": 0,
+            "maximum": 10,
+        })
+    );
+}
+
+#[test]
+fn it_should_emit_canonical_any_of() {
+    let a = normalize(json!({"type": ["string", "null"]}));
+    let b = normalize(
+        json!({"anyOf": [{"type": "null"}, {"type": "string"}, {"type": "string", "maxLength": 3}]}),
+    );
+
+    assert_eq!(a, b);
+    assert_eq!(a["anyOf"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn it_should_keep_schemas_equivalent() {
+    let original = json!({
+        "type": "object",
+        "properties": {
+            "id": {"anyOf": [{"type": "integer"}, {"type": "string", "format": "uuid"}]},
+            "tags": {"type": "array", "items": {"enum": ["a", "b"]}, "uniqueItems": true},
+        },
+        "required": ["id"],
+        "allOf": [{"properties": {"id": {"minimum": 1}}}],
+    });
+
+    let normalized = normalize(original.clone());
+    assert!(checkco::equivalent(schema(original), schema(normalized)).unwrap());
+}
+
+#[test]
+fn it_should_read_back_units_without_variants() {
+    let originals = vec![
+        json!({"properties": {"a": {"allOf": [{"type": "string"}, {"type": "integer"}]}}}),
+        json!({"additionalProperties": {"allOf": [{"type": "string"}, {"type": "null"}]}}),
+        json!({"items": {"allOf": [{"type": "boolean"}, {"type": "object"}]}}),
+    ];
+
+    for original in originals {
+        let normalized = normalize(original.clone());
+        assert!(normalized.to_string().contains(":false"));
+        assert!(checkco::equivalent(schema(original), schema(normalized)).unwrap());
+    }
+}
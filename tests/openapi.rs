@@ -0,0 +1,176 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+use checkco::{Error, Options, Verdict};
+use serde_json::Value;
+
+fn check(derived: Value, base: Value) -> Vec<(String, &'static str)> {
+    match checkco::check_openapi(&derived, &base, &Options::default()).unwrap() {
+        Verdict::Success => Vec::new(),
+        Verdict::Failure(mismatches) => mismatches
+            .into_iter()
+            .map(|m| (m.pointer, m.keyword))
+            .collect(),
+    }
+}
+
+fn document(operation: Value) -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {"title": "pets", "version": "1"},
+        "paths": {"/pets/{id}": {"post": operation}},
+        "components": {
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "id": {"type": "integer", "readOnly": true},
+                        "name": {"type": "string", "nullable": true},
+                    },
+                    "required": ["id"],
+                },
+            },
+        },
+    })
+}
+
+fn body(schema: Value) -> Value {
+    json!({"content": {"application/json": {"schema": schema}}})
+}
+
+#[test]
+fn it_should_accept_same_document() {
+    let doc = document(json!({
+        "requestBody": body(json!({"$ref": "#/components/schemas/Pet"})),
+        "responses": {"200": body(json!({"$ref": "#/components/schemas/Pet"}))},
+    }));
+
+    assert_eq!(check(doc.clone(), doc), vec![]);
+}
+
+#[test]
+fn it_should_check_request_bodies_contravariantly() {
+    let base = document(json!({
+        "requestBody": body(json!({"type": "integer"})),
+        "responses": {},
+    }));
+
+    let widened = document(json!({
+        "requestBody": body(json!({"type": "number"})),
+        "responses": {},
+    }));
+
+    assert_eq!(check(widened.clone(), base.clone()), vec![]);
+    assert_eq!(
+        check(base, widened),
+        vec![(
+            "/paths/~1pets~1{id}/post/requestBody/content/application~1json/schema".into(),
+            "type"
+        )]
+    );
+}
+
+#[test]
+fn it_should_check_responses_covariantly() {
+    let base = document(json!({
+        "responses": {"200": body(json!({"type": "number"}))},
+    }));
+
+    let narrowed = document(json!({
+        "responses": {"200": body(json!({"type": "integer"}))},
+    }));
+
+    assert_eq!(check(narrowed.clone(), base.clone()), vec![]);
+    assert_eq!(check(base, narrowed).len(), 1);
+}
+
+#[test]
+fn it_should_honor_nullable() {
+    let base = document(json!({
+        "responses": {"200": body(json!({"type": "string", "nullable": true}))},
+    }));
+
+    let derived = document(json!({
+        "responses": {"200": body(json!({"type": "string"}))},
+    }));
+
+    assert_eq!(check(derived.clone(), base.clone()), vec![]);
+    assert_eq!(check(base, derived).len(), 1);
+}
+
+#[test]
+fn it_should_ignore_read_only_properties_in_requests() {
+    let base = document(json!({
+        "requestBody": body(json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}},
+        })),
+        "responses": {},
+    }));
+
+    let derived = document(json!({
+        "requestBody": body(json!({"$ref": "#/components/schemas/Pet"})),
+        "responses": {},
+    }));
+
+    assert_eq!(check(derived, base), vec![]);
+}
+
+#[test]
+fn it_should_report_new_required_parameters() {
+    let base = document(json!({"responses": {}}));
+    let derived = document(json!({
+        "parameters": [
+            {"name": "id", "in": "path", "required": true, "schema": {"type": "string"}},
+            {"name": "limit", "in": "query", "schema": {"type": "integer"}},
+            {"name": "token", "in": "header", "required": true, "schema": {"type": "string"}},
+        ],
+        "responses": {},
+    }));
+
+    assert_eq!(
+        check(derived, base),
+        vec![
+            ("/paths/~1pets~1{id}/post/parameters/0".into(), "required"),
+            ("/paths/~1pets~1{id}/post/parameters/2".into(), "required"),
+        ]
+    );
+}
+
+#[test]
+fn it_should_report_removed_operations() {
+    let base = document(json!({"responses": {}}));
+    let mut derived = base.clone();
+    derived["paths"]["/pets/{petId}"] = json!({"get": {"responses": {}}});
+    derived["paths"]
+        .as_object_mut()
+        .unwrap()
+        .remove("/pets/{id}");
+
+    assert_eq!(
+        check(derived, base),
+        vec![("/paths/~1pets~1{petId}/post".into(), "operation")]
+    );
+}
+
+#[test]
+fn it_should_report_unknown_statuses() {
+    let base = document(json!({"responses": {"2XX": body(json!({}))}}));
+    let derived = document(json!({"responses": {"201": body(json!({})), "404": {}}}));
+
+    assert_eq!(
+        check(derived, base),
+        vec![("/paths/~1pets~1{id}/post/responses/404".into(), "status")]
+    );
+}
+
+#[test]
+fn it_should_reject_swagger() {
+    let swagger = json!({"swagger": "2.0", "paths": {}});
+
+    match checkco::check_openapi(&swagger, &swagger, &Options::default()) {
+        Err(Error::Unsupported(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
@@ -0,0 +1,68 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use checkco::{Error, Schema};
+use common::{is_compatible, schema};
+
+const SOURCE: &str = r#"
+    syntax = "proto3";
+    package users.v1;
+
+    message User {
+        string user_id = 1;
+        int32 age = 2;
+        int64 balance = 3;
+        repeated string tags = 4;
+        Role role = 5;
+    }
+
+    enum Role {
+        ROLE_UNSPECIFIED = 0;
+        ROLE_ADMIN = 1;
+    }
+"#;
+
+fn published() -> Schema {
+    schema(json!({
+        "type": "object",
+        "properties": {
+            "userId": {"type": "string"},
+            "age": {"type": "integer"},
+            "balance": {"type": "string"},
+            "tags": {"type": "array", "items": {"type": "string"}},
+            "role": {"enum": ["ROLE_UNSPECIFIED", "ROLE_ADMIN"]},
+        },
+    }))
+}
+
+#[test]
+fn it_should_verify_published_schemas() {
+    let proto = checkco::from_proto(SOURCE, "User").unwrap();
+    assert!(is_compatible(proto, published()));
+
+    let proto = checkco::from_proto(SOURCE, "users.v1.User").unwrap();
+    assert!(!is_compatible(published(), proto));
+}
+
+#[test]
+fn it_should_detect_wrong_encodings() {
+    let proto = checkco::from_proto(SOURCE, "User").unwrap();
+
+    let published = schema(json!({
+        "type": "object",
+        "properties": {"balance": {"type": "integer"}},
+    }));
+
+    assert!(!is_compatible(proto, published));
+}
+
+#[test]
+fn it_should_fail_on_unknown_messages() {
+    match checkco::from_proto(SOURCE, "Group") {
+        Err(Error::UnresolvedRef(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
@@ -0,0 +1,360 @@
+//! Checks that `check` never succeeds for a pair of schemas
+//! if some instance is valid under the derived schema, but not under the base one.
+
+extern crate checkco;
+extern crate proptest;
+extern crate regex;
+#[macro_use]
+extern crate serde_json;
+
+use proptest::prelude::*;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use checkco::{Schema, Verdict};
+
+const KEYS: &[&str] = &["a", "b", "c"];
+const PATTERNS: &[&str] = &["^a", "[ab]", "c$"];
+const TYPES: &[&str] = &[
+    "null", "boolean", "integer", "number", "string", "array", "object",
+];
+
+fn scalar() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        (-4i64..4).prop_map(Value::from),
+        (-8i64..8).prop_map(|n| Value::from(n as f64 / 2.)),
+        prop::sample::select(vec!["", "a", "b", "ab", "ba"]).prop_map(Value::from),
+    ]
+}
+
+fn instance() -> impl Strategy<Value = Value> {
+    scalar().prop_recursive(3, 16, 3, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::from),
+            prop::collection::btree_map(prop::sample::select(KEYS), inner, 0..4).prop_map(
+                |props| {
+                    let props = props.into_iter().map(|(k, v)| (k.to_string(), v));
+                    Value::Object(props.collect())
+                }
+            ),
+        ]
+    })
+}
+
+fn keyword() -> impl Strategy<Value = Value> {
+    let count = 0u32..3;
+    let bound = -3i64..3;
+
+    prop_oneof![
+        Just(json!({})),
+        prop::sample::select(TYPES).prop_map(|t| json!({ "type": t })),
+        prop::sample::subsequence(TYPES, 1..3).prop_map(|t| json!({ "type": t })),
+        bound.clone().prop_map(|n| json!({ "minimum": n })),
+        bound.clone().prop_map(|n| json!({ "maximum": n })),
+        bound.clone().prop_map(|n| json!({ "exclusiveMinimum": n })),
+        bound.prop_map(|n| json!({ "exclusiveMaximum": n })),
+        (1u32..4).prop_map(|n| json!({ "multipleOf": n })),
+        count.clone().prop_map(|n| json!({ "minLength": n })),
+        count.clone().prop_map(|n| json!({ "maxLength": n })),
+        count.clone().prop_map(|n| json!({ "minItems": n })),
+        count.clone().prop_map(|n| json!({ "maxItems": n })),
+        count.clone().prop_map(|n| json!({ "minProperties": n })),
+        count.prop_map(|n| json!({ "maxProperties": n })),
+        Just(json!({"uniqueItems": true})),
+        Just(json!({"pattern": "^a"})),
+        scalar().prop_map(|v| json!({ "const": v })),
+        prop::collection::vec(scalar(), 1..3).prop_map(|v| json!({ "enum": v })),
+        prop::sample::subsequence(KEYS, 1..3).prop_map(|k| json!({ "required": k })),
+    ]
+}
+
+fn schema() -> impl Strategy<Value = Value> {
+    keyword().prop_recursive(3, 24, 3, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 1..3).prop_map(|v| json!({ "allOf": v })),
+            prop::collection::vec(inner.clone(), 1..3).prop_map(|v| json!({ "anyOf": v })),
+            inner.clone().prop_map(|s| json!({ "items": s })),
+            inner.clone().prop_map(|s| json!({ "contains": s })),
+            inner
+                .clone()
+                .prop_map(|s| json!({ "additionalProperties": s })),
+            inner.clone().prop_map(|s| json!({ "propertyNames": s })),
+            (prop::sample::select(KEYS), inner.clone())
+                .prop_map(|(k, s)| json!({ "properties": { k: s } })),
+            (prop::sample::select(PATTERNS), inner.clone())
+                .prop_map(|(p, s)| json!({ "patternProperties": { p: s } })),
+            (inner.clone(), inner).prop_map(|(a, b)| combine(a, b)),
+        ]
+    })
+}
+
+/// Puts keywords of both schemas into one, so they're checked together.
+fn combine(a: Value, b: Value) -> Value {
+    let (mut a, b) = match (a, b) {
+        (Value::Object(a), Value::Object(b)) => (a, b),
+        _ => unreachable!(),
+    };
+
+    for (key, value) in b {
+        if !a.contains_key(&key) {
+            a.insert(key, value);
+        }
+    }
+
+    Value::Object(a)
+}
+
+/// Pairs of unrelated schemas rarely succeed, so narrowed ones are generated too.
+fn pair() -> impl Strategy<Value = (Value, Value)> {
+    prop_oneof![
+        (schema(), schema()),
+        (schema(), schema()).prop_map(|(base, extra)| (json!({"allOf": [base, extra]}), base)),
+        (schema(), schema()).prop_map(|(derived, extra)| {
+            let base = json!({"anyOf": [derived, extra]});
+            (derived, base)
+        }),
+        // Literals of the base probe how its `const` and `enum` are combined.
+        schema().prop_flat_map(|base| {
+            let mut values = vec![Value::Null];
+            literals(&base, &mut values);
+
+            let derived = prop::sample::select(values).prop_map(|v| json!({ "const": v }));
+            (derived, Just(base))
+        }),
+    ]
+}
+
+/// Collects values of `const` and `enum`, which random instances rarely hit.
+fn literals(schema: &Value, out: &mut Vec<Value>) {
+    match schema {
+        Value::Object(schema) => {
+            for (key, value) in schema {
+                match key.as_str() {
+                    "const" => out.push(value.clone()),
+                    "enum" => out.extend(array(value).iter().cloned()),
+                    _ => literals(value, out),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| literals(item, out)),
+        _ => {}
+    }
+}
+
+fn parse(value: &Value) -> Schema {
+    serde_json::from_value(value.clone()).unwrap()
+}
+
+/// Validates the instance against the raw schema, evaluating `allOf` branches one by one,
+/// so the oracle doesn't share the merging of units with the checker.
+fn valid(schema: &Value, instance: &Value) -> bool {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        _ => unreachable!(),
+    };
+
+    schema
+        .iter()
+        .all(|(keyword, value)| match keyword.as_str() {
+            "allOf" => array(value).iter().all(|s| valid(s, instance)),
+            "anyOf" => array(value).iter().any(|s| valid(s, instance)),
+            "type" => match value {
+                Value::String(t) => is_type(instance, t),
+                _ => array(value)
+                    .iter()
+                    .any(|t| is_type(instance, t.as_str().unwrap())),
+            },
+            "const" => equal(value, instance),
+            "enum" => array(value).iter().any(|v| equal(v, instance)),
+            "additionalProperties" => instance
+                .as_object()
+                .is_none_or(|props| valid_additional(schema, props)),
+            _ => match instance {
+                Value::Number(n) => valid_number(keyword, number(value), n.as_f64().unwrap()),
+                Value::String(s) => valid_string(keyword, value, s),
+                Value::Array(items) => valid_array(keyword, value, items),
+                Value::Object(props) => valid_object(keyword, value, props),
+                _ => true,
+            },
+        })
+}
+
+fn valid_number(keyword: &str, bound: f64, n: f64) -> bool {
+    match keyword {
+        "minimum" => n >= bound,
+        "maximum" => n <= bound,
+        "exclusiveMinimum" => n > bound,
+        "exclusiveMaximum" => n < bound,
+        "multipleOf" => (n / bound).fract() == 0.,
+        _ => true,
+    }
+}
+
+fn valid_string(keyword: &str, value: &Value, s: &str) -> bool {
+    let length = s.chars().count() as f64;
+
+    match keyword {
+        "minLength" => length >= number(value),
+        "maxLength" => length <= number(value),
+        "pattern" => is_match(value.as_str().unwrap(), s),
+        _ => true,
+    }
+}
+
+fn valid_array(keyword: &str, value: &Value, items: &[Value]) -> bool {
+    let length = items.len() as f64;
+
+    match keyword {
+        "minItems" => length >= number(value),
+        "maxItems" => length <= number(value),
+        "uniqueItems" => items
+            .iter()
+            .enumerate()
+            .all(|(i, a)| items[i + 1..].iter().all(|b| !equal(a, b))),
+        "items" => items.iter().all(|item| valid(value, item)),
+        "contains" => items.iter().any(|item| valid(value, item)),
+        _ => true,
+    }
+}
+
+fn valid_object(keyword: &str, value: &Value, props: &Map<String, Value>) -> bool {
+    let length = props.len() as f64;
+
+    match keyword {
+        "minProperties" => length >= number(value),
+        "maxProperties" => length <= number(value),
+        "required" => array(value)
+            .iter()
+            .all(|k| props.contains_key(k.as_str().unwrap())),
+        "propertyNames" => props.keys().all(|k| valid(value, &Value::from(k.as_str()))),
+        "properties" => props
+            .iter()
+            .all(|(k, v)| value.get(k).is_none_or(|s| valid(s, v))),
+        "patternProperties" => props.iter().all(|(k, v)| {
+            value
+                .as_object()
+                .unwrap()
+                .iter()
+                .all(|(p, s)| !is_match(p, k) || valid(s, v))
+        }),
+        _ => true,
+    }
+}
+
+/// `additionalProperties` depends on sibling keywords, so it's checked with them.
+fn valid_additional(schema: &Map<String, Value>, props: &Map<String, Value>) -> bool {
+    let additional = match schema.get("additionalProperties") {
+        Some(additional) => additional,
+        None => return true,
+    };
+
+    props.iter().all(|(k, v)| {
+        let declared = schema.get("properties").is_some_and(|p| p.get(k).is_some());
+        let matched = schema
+            .get("patternProperties")
+            .and_then(Value::as_object)
+            .is_some_and(|p| p.keys().any(|p| is_match(p, k)));
+
+        declared || matched || valid(additional, v)
+    })
+}
+
+fn is_type(instance: &Value, type_: &str) -> bool {
+    match (instance, type_) {
+        (Value::Null, "null")
+        | (Value::Bool(_), "boolean")
+        | (Value::Number(_), "number")
+        | (Value::String(_), "string")
+        | (Value::Array(_), "array")
+        | (Value::Object(_), "object") => true,
+        (Value::Number(n), "integer") => n.as_f64().unwrap().fract() == 0.,
+        _ => false,
+    }
+}
+
+/// Compares values structurally, treating `1` and `1.0` as equal.
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| equal(v, w)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn is_match(pattern: &str, string: &str) -> bool {
+    Regex::new(pattern).unwrap().is_match(string)
+}
+
+fn array(value: &Value) -> &Vec<Value> {
+    value.as_array().unwrap()
+}
+
+fn number(value: &Value) -> f64 {
+    value.as_f64().unwrap_or(0.)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1024))]
+
+    #[test]
+    fn check_should_be_sound(
+        (derived, base) in pair(),
+        mut instances in prop::collection::vec(instance(), 32),
+    ) {
+        match checkco::check(parse(&derived), parse(&base)) {
+            Ok(Verdict::Success) => {}
+            _ => return Ok(()),
+        }
+
+        literals(&derived, &mut instances);
+
+        for instance in &instances {
+            if valid(&derived, instance) {
+                prop_assert!(valid(&base, instance), "instance: {}", instance);
+            }
+        }
+    }
+}
+
+#[test]
+fn it_should_restrict_patterns_by_additional_props_of_other_branches() {
+    let derived = json!({
+        "type": "object",
+        "patternProperties": {"^x": {}},
+        "additionalProperties": {"type": "string"}
+    });
+
+    let base = json!({"allOf": [
+        {"type": "object", "patternProperties": {"^x": {}}},
+        {"type": "object", "additionalProperties": {"type": "string"}}
+    ]});
+
+    let instance = json!({"x1": 5});
+    assert!(valid(&derived, &instance) && !valid(&base, &instance));
+
+    match checkco::check(parse(&derived), parse(&base)) {
+        Ok(Verdict::Failure(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn it_should_keep_only_enum_values_equal_to_const() {
+    let derived = json!({"const": 1});
+    let base = json!({"const": 2, "enum": [1, 2]});
+
+    let instance = json!(1);
+    assert!(valid(&derived, &instance) && !valid(&base, &instance));
+
+    match checkco::check(parse(&derived), parse(&base)) {
+        Ok(Verdict::Failure(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
@@ -0,0 +1,205 @@
+//! Runs the official JSON-Schema-Test-Suite, vendored into `tests/suite`.
+//!
+//! Run with `--nocapture` to see pass rates per keyword.
+
+extern crate checkco;
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use checkco::{Error, Schema};
+use serde_json::Value;
+
+/// Expected `(keyword, passed, skipped)` counts, so both lost and gained coverage show up.
+///
+/// Skipped cases use unsupported features. Update the counts when they become supported.
+const DRAFT6: &[(&str, usize, usize)] = &[
+    ("additionalItems", 3, 10),
+    ("additionalProperties", 15, 0),
+    ("allOf", 22, 8),
+    ("anyOf", 20, 0),
+    ("boolean_schema", 18, 0),
+    ("const", 50, 0),
+    ("contains", 18, 0),
+    ("default", 4, 0),
+    ("definitions", 0, 2),
+    ("dependencies", 0, 32),
+    ("enum", 33, 0),
+    ("exclusiveMaximum", 4, 0),
+    ("exclusiveMinimum", 4, 0),
+    ("format", 54, 0),
+    ("items", 11, 15),
+    ("maxItems", 4, 0),
+    ("maxLength", 5, 0),
+    ("maxProperties", 8, 0),
+    ("maximum", 8, 0),
+    ("minItems", 4, 0),
+    ("minLength", 5, 0),
+    ("minProperties", 6, 0),
+    ("minimum", 11, 0),
+    ("multipleOf", 9, 0),
+    ("not", 3, 9),
+    ("oneOf", 0, 27),
+    ("pattern", 9, 0),
+    ("patternProperties", 22, 0),
+    ("properties", 20, 0),
+    ("propertyNames", 10, 0),
+    ("ref", 13, 24),
+    ("refRemote", 0, 15),
+    ("required", 9, 0),
+    ("type", 80, 0),
+    ("uniqueItems", 38, 26),
+];
+
+const DRAFT7: &[(&str, usize, usize)] = &[
+    ("additionalItems", 3, 10),
+    ("additionalProperties", 15, 0),
+    ("allOf", 22, 8),
+    ("anyOf", 20, 0),
+    ("boolean_schema", 18, 0),
+    ("const", 50, 0),
+    ("contains", 18, 0),
+    ("default", 4, 0),
+    ("definitions", 0, 2),
+    ("dependencies", 0, 32),
+    ("enum", 33, 0),
+    ("exclusiveMaximum", 4, 0),
+    ("exclusiveMinimum", 4, 0),
+    ("format", 102, 0),
+    ("if-then-else", 8, 18),
+    ("items", 11, 15),
+    ("maxItems", 4, 0),
+    ("maxLength", 5, 0),
+    ("maxProperties", 8, 0),
+    ("maximum", 8, 0),
+    ("minItems", 4, 0),
+    ("minLength", 5, 0),
+    ("minProperties", 6, 0),
+    ("minimum", 11, 0),
+    ("multipleOf", 9, 0),
+    ("not", 3, 9),
+    ("oneOf", 0, 27),
+    ("pattern", 9, 0),
+    ("patternProperties", 22, 0),
+    ("properties", 20, 0),
+    ("propertyNames", 10, 0),
+    ("ref", 13, 24),
+    ("refRemote", 0, 15),
+    ("required", 9, 0),
+    ("type", 80, 0),
+    ("uniqueItems", 38, 26),
+];
+
+#[derive(Default)]
+struct Stats {
+    passed: usize,
+    failed: Vec<String>,
+    skipped: usize,
+}
+
+fn run(draft: &str) -> BTreeMap<String, Stats> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/suite")
+        .join(draft);
+    let mut report = BTreeMap::new();
+
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let keyword = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let groups: Vec<Value> = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let stats: &mut Stats = report.entry(keyword).or_default();
+
+        for group in &groups {
+            for case in group["tests"].as_array().unwrap() {
+                let name = format!(
+                    "{} / {}",
+                    group["description"].as_str().unwrap(),
+                    case["description"].as_str().unwrap()
+                );
+                let schema = serde_json::from_value::<Schema>(group["schema"].clone());
+
+                match schema
+                    .map_err(Error::from)
+                    .and_then(|s| checkco::validate(s, &case["data"]))
+                {
+                    Ok(valid) if Some(valid) == case["valid"].as_bool() => stats.passed += 1,
+                    Ok(_) => stats.failed.push(name),
+                    // Such schemas are rejected by `check` too, so they aren't conformance bugs.
+                    Err(Error::Unsupported(_)) | Err(Error::UnresolvedRef(_)) => stats.skipped += 1,
+                    Err(err) => stats.failed.push(format!("{}: {}", name, err)),
+                }
+            }
+        }
+    }
+
+    report
+}
+
+fn print(draft: &str, report: &BTreeMap<String, Stats>) {
+    println!("{}:", draft);
+
+    for (keyword, stats) in report {
+        // Skipped cases aren't supported, so they count against the rate.
+        let total = stats.passed + stats.failed.len() + stats.skipped;
+        let rate = if total == 0 {
+            "n/a".to_string()
+        } else {
+            format!("{:.1}%", 100. * stats.passed as f64 / total as f64)
+        };
+
+        println!(
+            "  {:<24} {:>6} ({}/{}, {} skipped)",
+            keyword, rate, stats.passed, total, stats.skipped
+        );
+
+        for name in &stats.failed {
+            println!("    FAILED {}", name);
+        }
+    }
+}
+
+fn conform(draft: &str, expected: &[(&str, usize, usize)]) {
+    let report = run(draft);
+    print(draft, &report);
+
+    let failed = report
+        .values()
+        .flat_map(|stats| &stats.failed)
+        .collect::<Vec<_>>();
+
+    assert!(failed.is_empty(), "failed cases: {:#?}", failed);
+
+    let actual = report
+        .iter()
+        .map(|(keyword, stats)| (keyword.as_str(), stats.passed, stats.skipped))
+        .collect::<Vec<_>>();
+
+    let changed = actual
+        .iter()
+        .filter(|counts| !expected.contains(counts))
+        .collect::<Vec<_>>();
+
+    let missing = expected
+        .iter()
+        .filter(|counts| !actual.contains(counts))
+        .collect::<Vec<_>>();
+
+    assert!(
+        changed.is_empty() && missing.is_empty(),
+        "(keyword, passed, skipped) counts are {:#?}, but expected {:#?}",
+        changed,
+        missing
+    );
+}
+
+#[test]
+fn draft6() {
+    conform("draft6", DRAFT6);
+}
+
+#[test]
+fn draft7() {
+    conform("draft7", DRAFT7);
+}
@@ -0,0 +1,70 @@
+extern crate checkco;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use checkco::Error;
+use common::{is_compatible, schema};
+
+const SOURCE: &str = r#"
+    export type Role = "admin" | "user";
+
+    export interface Entity {
+        id: number;
+    }
+
+    export interface User extends Entity {
+        name: string;
+        role: Role;
+        tags?: string[];
+        settings: Record<string, string | number>;
+    }
+"#;
+
+#[test]
+fn it_should_check_backend_schemas_against_declared_types() {
+    let backend = || {
+        schema(json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer"},
+                "name": {"type": "string", "maxLength": 64},
+                "role": {"enum": ["admin", "user"]},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "settings": {"type": "object", "additionalProperties": {"type": "string"}},
+            },
+            "required": ["id", "name", "role", "settings"],
+        }))
+    };
+
+    let frontend = checkco::from_typescript(SOURCE, "User").unwrap();
+    assert!(is_compatible(backend(), frontend));
+
+    let frontend = checkco::from_typescript(SOURCE, "User").unwrap();
+    assert!(!is_compatible(frontend, backend()));
+}
+
+#[test]
+fn it_should_detect_missing_required_properties() {
+    let backend = schema(json!({
+        "type": "object",
+        "properties": {"id": {"type": "integer"}, "name": {"type": "string"}},
+    }));
+
+    let frontend = checkco::from_typescript(SOURCE, "User").unwrap();
+    assert!(!is_compatible(backend, frontend));
+}
+
+#[test]
+fn it_should_fail_on_unknown_types() {
+    match checkco::from_typescript("type A = B;", "A") {
+        Err(Error::UnresolvedRef(name)) => assert_eq!(name, "B"),
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    match checkco::from_typescript("type A<T> = T[];", "A") {
+        Err(Error::Unsupported("generic types")) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}